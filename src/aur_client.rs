@@ -1,23 +1,65 @@
+use crate::package_index::PackageIndex;
+use crate::rate_limit::{self, TokenBucket};
+use crate::response_cache::ResponseCache;
+use crate::rss_feed::{self, FeedEntry};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A point in time as reported by the RPC: seconds since the Unix epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Timestamp(pub i64);
+
+impl Timestamp {
+    pub fn now() -> Self {
+        Timestamp(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0),
+        )
+    }
+
+    pub fn as_unix(self) -> i64 {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AurPackage {
+    #[serde(rename = "ID")]
+    pub id: Option<u64>,
     #[serde(rename = "Name")]
     pub name: String,
+    #[serde(rename = "PackageBaseID")]
+    pub package_base_id: Option<u64>,
+    #[serde(rename = "PackageBase")]
+    pub package_base: Option<String>,
     #[serde(rename = "Version")]
     pub version: String,
     #[serde(rename = "Description")]
     pub description: Option<String>,
     #[serde(rename = "Maintainer")]
     pub maintainer: Option<String>,
+    #[serde(rename = "Submitter")]
+    pub submitter: Option<String>,
+    #[serde(rename = "CoMaintainers")]
+    pub comaintainers: Option<Vec<String>>,
     #[serde(rename = "NumVotes")]
     pub votes: Option<i32>,
     #[serde(rename = "Popularity")]
     pub popularity: Option<f64>,
     #[serde(rename = "OutOfDate")]
-    pub out_of_date: Option<i64>,
+    pub out_of_date: Option<Timestamp>,
+    #[serde(rename = "FirstSubmitted")]
+    pub first_submitted: Option<Timestamp>,
     #[serde(rename = "LastModified")]
-    pub last_modified: Option<i64>,
+    pub last_modified: Option<Timestamp>,
     #[serde(rename = "URL")]
     pub url: Option<String>,
     #[serde(rename = "URLPath")]
@@ -26,61 +68,856 @@ pub struct AurPackage {
     pub depends: Option<Vec<String>>,
     #[serde(rename = "MakeDepends")]
     pub makedepends: Option<Vec<String>>,
+    #[serde(rename = "CheckDepends")]
+    pub checkdepends: Option<Vec<String>>,
     #[serde(rename = "OptDepends")]
     pub optdepends: Option<Vec<String>>,
     #[serde(rename = "Conflicts")]
     pub conflicts: Option<Vec<String>>,
+    #[serde(rename = "Provides")]
+    pub provides: Option<Vec<String>>,
+    #[serde(rename = "Replaces")]
+    pub replaces: Option<Vec<String>>,
     #[serde(rename = "License")]
     pub license: Option<Vec<String>>,
+    #[serde(rename = "Keywords")]
+    pub keywords: Option<Vec<String>>,
+    #[serde(rename = "Groups")]
+    pub groups: Option<Vec<String>>,
+}
+
+/// Which package field a search matches against (the RPC's `by` argument).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchField {
+    Name,
+    #[default]
+    NameDesc,
+    Maintainer,
+    Depends,
+    MakeDepends,
+    OptDepends,
+    CheckDepends,
+    Submitter,
+    Provides,
+    Conflicts,
+    Replaces,
+    Keywords,
+    Groups,
+    CoMaintainers,
+}
+
+impl SearchField {
+    pub const ALL: [SearchField; 14] = [
+        SearchField::NameDesc,
+        SearchField::Name,
+        SearchField::Maintainer,
+        SearchField::Depends,
+        SearchField::MakeDepends,
+        SearchField::OptDepends,
+        SearchField::CheckDepends,
+        SearchField::Submitter,
+        SearchField::Provides,
+        SearchField::Conflicts,
+        SearchField::Replaces,
+        SearchField::Keywords,
+        SearchField::Groups,
+        SearchField::CoMaintainers,
+    ];
+
+    pub fn as_param(self) -> &'static str {
+        match self {
+            SearchField::Name => "name",
+            SearchField::NameDesc => "name-desc",
+            SearchField::Maintainer => "maintainer",
+            SearchField::Depends => "depends",
+            SearchField::MakeDepends => "makedepends",
+            SearchField::OptDepends => "optdepends",
+            SearchField::CheckDepends => "checkdepends",
+            SearchField::Submitter => "submitter",
+            SearchField::Provides => "provides",
+            SearchField::Conflicts => "conflicts",
+            SearchField::Replaces => "replaces",
+            SearchField::Keywords => "keywords",
+            SearchField::Groups => "groups",
+            SearchField::CoMaintainers => "comaintainers",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SearchField::Name => "Name",
+            SearchField::NameDesc => "Name & Description",
+            SearchField::Maintainer => "Maintainer",
+            SearchField::Depends => "Depends",
+            SearchField::MakeDepends => "Make Depends",
+            SearchField::OptDepends => "Optional Depends",
+            SearchField::CheckDepends => "Check Depends",
+            SearchField::Submitter => "Submitter",
+            SearchField::Provides => "Provides",
+            SearchField::Conflicts => "Conflicts",
+            SearchField::Replaces => "Replaces",
+            SearchField::Keywords => "Keywords",
+            SearchField::Groups => "Groups",
+            SearchField::CoMaintainers => "Co-maintainers",
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
 struct AurResponse {
+    #[serde(default)]
     results: Vec<AurPackage>,
 }
 
-pub async fn search_aur(query: &str) -> Result<Vec<AurPackage>, Box<dyn std::error::Error>> {
-    let encoded_query = urlencoding::encode(query);
-    let url = format!(
-        "https://aur.archlinux.org/rpc?v=5&type=search&arg={}",
-        encoded_query
-    );
-
-    let client = reqwest::Client::new();
-    let response = client.get(&url).send().await?;
-    let aur_response: AurResponse = response.json().await?;
-
-    Ok(aur_response.results)
-}
-
-pub async fn get_package_info(package_name: &str) -> Result<AurPackage, Box<dyn std::error::Error>> {
-    let encoded_query = urlencoding::encode(package_name);
-    let url = format!(
-        "https://aur.archlinux.org/rpc?v=5&type=info&arg={}",
-        encoded_query
-    );
-
-    let client = reqwest::Client::new();
-    let response = client.get(&url).send().await?;
-    let aur_response: AurResponse = response.json().await?;
-
-    aur_response.results.into_iter().next()
-        .ok_or_else(|| "Package not found".into())
-}
-
-pub async fn get_recent_packages(count: usize) -> Result<Vec<AurPackage>, Box<dyn std::error::Error>> {
-    // Test connection with a simple search
-    let url = "https://aur.archlinux.org/rpc?v=5&type=search&by=name&arg=";
-    
-    let client = reqwest::Client::new();
-    let response = client.get(url).send().await?;
-    let mut aur_response: AurResponse = response.json().await?;
-    
-    // Sort by last modified
-    aur_response.results.sort_by(|a, b| {
-        b.last_modified.unwrap_or(0).cmp(&a.last_modified.unwrap_or(0))
-    });
-    
-    aur_response.results.truncate(count);
-    Ok(aur_response.results)
+#[derive(Debug)]
+pub enum AurError {
+    /// The request never got a response (DNS, TLS, connection refused...).
+    Network(reqwest::Error),
+    Timeout,
+    /// The server answered with a non-success status code.
+    Http(reqwest::StatusCode),
+    /// The body was not the JSON we expected.
+    Json(serde_json::Error),
+    /// The RPC answered with `"type": "error"`, e.g. "Too many package results."
+    Rpc(String),
+    NotFound(String),
+    /// A `file://` feed could not be read.
+    Io(std::io::Error),
+    /// An RSS feed could not be parsed.
+    Feed(String),
+    /// The AUR kept answering 429/503 after all retries. Carries the
+    /// server's `Retry-After`, if it sent one.
+    RateLimited(Option<Duration>),
+}
+
+impl fmt::Display for AurError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AurError::Network(e) => write!(f, "Network error: {}", e),
+            AurError::Timeout => write!(f, "The request to the AUR timed out"),
+            AurError::Http(status) => write!(f, "The AUR responded with HTTP {}", status),
+            AurError::Json(e) => write!(f, "Malformed response from the AUR: {}", e),
+            AurError::Rpc(message) => write!(f, "{}", message),
+            AurError::NotFound(name) => write!(f, "No package named {}", name),
+            AurError::Io(e) => write!(f, "{}", e),
+            AurError::Feed(message) => write!(f, "Malformed RSS feed: {}", message),
+            AurError::RateLimited(Some(retry_after)) => write!(
+                f,
+                "The AUR is rate-limiting requests; try again in {} s",
+                retry_after.as_secs()
+            ),
+            AurError::RateLimited(None) => write!(f, "The AUR is rate-limiting requests"),
+        }
+    }
+}
+
+impl std::error::Error for AurError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AurError::Network(e) => Some(e),
+            AurError::Json(e) => Some(e),
+            AurError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl AurError {
+    /// True when the AUR could not give us an answer at all (down,
+    /// unreachable or throttling us), as opposed to it answering with
+    /// something we did not like.
+    pub fn is_unreachable(&self) -> bool {
+        match self {
+            AurError::Network(_) | AurError::Timeout | AurError::RateLimited(_) => true,
+            AurError::Http(status) => status.is_server_error(),
+            _ => false,
+        }
+    }
+}
+
+impl From<reqwest::Error> for AurError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            AurError::Timeout
+        } else if let Some(status) = e.status() {
+            AurError::Http(status)
+        } else {
+            AurError::Network(e)
+        }
+    }
+}
+
+impl From<std::io::Error> for AurError {
+    fn from(e: std::io::Error) -> Self {
+        AurError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for AurError {
+    fn from(e: serde_json::Error) -> Self {
+        AurError::Json(e)
+    }
+}
+
+pub const DEFAULT_BASE_URL: &str = "https://aur.archlinux.org";
+
+/// Longest request URL we send. The AUR's web server starts rejecting
+/// URLs well past this, so multi-info lookups are split to stay under it.
+const MAX_URL_LEN: usize = 4000;
+
+/// Nightly dump of every package's full metadata, served from the AUR root.
+const INDEX_ARCHIVE: &str = "packages-meta-ext-v1.json.gz";
+/// Validators and last-check time for the downloaded archive.
+const INDEX_META: &str = "packages-meta-ext-v1.meta.json";
+/// The archive is several megabytes, so it gets more time than RPC calls.
+const INDEX_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How long a cached response may be served past its TTL while it is
+/// revalidated in the background.
+const STALE_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Attempts per idempotent request, including the first.
+const MAX_ATTEMPTS: u32 = 4;
+const BASE_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// A `Retry-After` longer than this is not worth waiting for in the UI.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(120);
+/// Lowest accepted refill rate; zero or negative rates would never refill.
+const MIN_REQUESTS_PER_SEC: f64 = 0.01;
+
+/// Progress the client reports while it is holding a request back.
+#[derive(Debug, Clone)]
+pub enum ClientStatus {
+    /// The server sent 429/503; waiting before the next attempt.
+    RateLimited { retry_in: Duration },
+    /// The request failed transiently; waiting before the next attempt.
+    Retrying { retry_in: Duration, reason: String },
+}
+
+impl fmt::Display for ClientStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientStatus::RateLimited { retry_in } => {
+                write!(f, "Rate-limited, retrying in {} s", retry_in.as_secs().max(1))
+            }
+            ClientStatus::Retrying { retry_in, reason } => {
+                write!(f, "{}, retrying in {} s", reason, retry_in.as_secs().max(1))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Endpoint {
+    Search,
+    Info,
+    Suggest,
+}
+
+impl Endpoint {
+    /// How long a cached response is considered fresh.
+    fn ttl(self) -> Duration {
+        match self {
+            Endpoint::Search => Duration::from_secs(10 * 60),
+            Endpoint::Info => Duration::from_secs(60 * 60),
+            Endpoint::Suggest => Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IndexMeta {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    checked_at: i64,
+}
+
+/// Handle to an AUR instance. Cheap to clone; all clones share one
+/// connection pool.
+#[derive(Clone)]
+pub struct AurClient {
+    http: reqwest::Client,
+    base_url: String,
+    new_packages_feed: String,
+    updated_packages_feed: String,
+    cache_dir: PathBuf,
+    cache: ResponseCache,
+    index: Arc<RwLock<Option<Arc<PackageIndex>>>>,
+    limiter: Arc<Mutex<TokenBucket>>,
+    status: broadcast::Sender<ClientStatus>,
+}
+
+pub struct AurClientBuilder {
+    base_url: String,
+    new_packages_feed: Option<String>,
+    updated_packages_feed: Option<String>,
+    cache_dir: PathBuf,
+    timeout: Duration,
+    connect_timeout: Duration,
+    user_agent: String,
+    burst: u32,
+    requests_per_sec: f64,
+}
+
+impl Default for AurClientBuilder {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            new_packages_feed: None,
+            updated_packages_feed: None,
+            cache_dir: crate::paths::cache_dir(),
+            timeout: Duration::from_secs(20),
+            connect_timeout: Duration::from_secs(10),
+            user_agent: format!("aurlooker/{}", env!("CARGO_PKG_VERSION")),
+            burst: 20,
+            requests_per_sec: 1.0,
+        }
+    }
+}
+
+impl AurClientBuilder {
+    /// Root of the AUR web interface, e.g. `https://aur.archlinux.org`.
+    /// The RPC endpoint is expected at `<base_url>/rpc`.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Feed of newly submitted packages. Defaults to `<base_url>/rss/`.
+    /// `file://` URLs are read from disk.
+    pub fn new_packages_feed(mut self, url: impl Into<String>) -> Self {
+        self.new_packages_feed = Some(url.into());
+        self
+    }
+
+    /// Feed of recently updated packages. Defaults to `<base_url>/rss/modified`.
+    /// `file://` URLs are read from disk.
+    pub fn updated_packages_feed(mut self, url: impl Into<String>) -> Self {
+        self.updated_packages_feed = Some(url.into());
+        self
+    }
+
+    /// Where the offline package index and cached RPC responses are stored.
+    pub fn cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = dir.into();
+        self
+    }
+
+    /// Limit for a whole request, body included. Defaults to 20 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Limit for establishing a connection. Defaults to 10 seconds.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// `User-Agent` sent with every request. Defaults to `aurlooker/<version>`.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Client-side rate limit shared by every clone of the client: up to
+    /// `burst` requests at once, refilled at `requests_per_sec` (at least
+    /// `MIN_REQUESTS_PER_SEC`).
+    pub fn rate_limit(mut self, burst: u32, requests_per_sec: f64) -> Self {
+        self.burst = burst.max(1);
+        // `max` also replaces NaN
+        self.requests_per_sec = requests_per_sec.max(MIN_REQUESTS_PER_SEC);
+        self
+    }
+
+    pub fn build(self) -> Result<AurClient, AurError> {
+        let http = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .connect_timeout(self.connect_timeout)
+            .user_agent(self.user_agent)
+            .build()?;
+
+        Ok(AurClient {
+            http,
+            new_packages_feed: self
+                .new_packages_feed
+                .unwrap_or_else(|| format!("{}/rss/", self.base_url)),
+            updated_packages_feed: self
+                .updated_packages_feed
+                .unwrap_or_else(|| format!("{}/rss/modified", self.base_url)),
+            base_url: self.base_url,
+            cache: ResponseCache::new(self.cache_dir.join("rpc")),
+            cache_dir: self.cache_dir,
+            index: Arc::new(RwLock::new(None)),
+            limiter: Arc::new(Mutex::new(TokenBucket::new(self.burst, self.requests_per_sec))),
+            status: broadcast::channel(16).0,
+        })
+    }
+}
+
+impl AurClient {
+    pub fn builder() -> AurClientBuilder {
+        AurClientBuilder::default()
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Receives a `ClientStatus` whenever a request is delayed for a retry.
+    pub fn subscribe_status(&self) -> broadcast::Receiver<ClientStatus> {
+        self.status.subscribe()
+    }
+
+    /// Waits for a token from the shared rate limiter.
+    async fn acquire(&self) {
+        loop {
+            let wait = match self.limiter.lock() {
+                Ok(mut bucket) => match bucket.try_take() {
+                    Ok(()) => return,
+                    Err(wait) => wait,
+                },
+                Err(_) => return,
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Sends an idempotent request, retrying with exponential backoff on
+    /// timeouts, connection failures, 429 and 5xx. `Retry-After` is honored
+    /// when the server sends one.
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, AurError> {
+        let mut attempt = 0;
+        loop {
+            self.acquire().await;
+            let this_try = request
+                .try_clone()
+                .expect("GET requests without a streaming body can be cloned");
+
+            let (retry_in, status) = match this_try.send().await {
+                Ok(response) => {
+                    let status = response.status();
+                    let throttled = status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                        || status == reqwest::StatusCode::SERVICE_UNAVAILABLE;
+                    if !throttled && !status.is_server_error() {
+                        return Ok(response);
+                    }
+
+                    let retry_after = retry_after(&response);
+                    if attempt + 1 >= MAX_ATTEMPTS
+                        || retry_after.is_some_and(|wait| wait > MAX_RETRY_AFTER)
+                    {
+                        return Err(if throttled {
+                            AurError::RateLimited(retry_after)
+                        } else {
+                            AurError::Http(status)
+                        });
+                    }
+
+                    let retry_in = retry_after
+                        .unwrap_or_else(|| rate_limit::backoff(attempt, BASE_BACKOFF, MAX_BACKOFF));
+                    let status = if throttled {
+                        ClientStatus::RateLimited { retry_in }
+                    } else {
+                        ClientStatus::Retrying {
+                            retry_in,
+                            reason: format!("HTTP {}", status),
+                        }
+                    };
+                    (retry_in, status)
+                }
+                Err(e) => {
+                    let error = AurError::from(e);
+                    if !error.is_unreachable() || attempt + 1 >= MAX_ATTEMPTS {
+                        return Err(error);
+                    }
+                    let retry_in = rate_limit::backoff(attempt, BASE_BACKOFF, MAX_BACKOFF);
+                    let reason = match error {
+                        AurError::Timeout => "Request timed out".to_string(),
+                        _ => "Connection failed".to_string(),
+                    };
+                    (retry_in, ClientStatus::Retrying { retry_in, reason })
+                }
+            };
+
+            // Nobody listening is fine.
+            let _ = self.status.send(status);
+            tokio::time::sleep(retry_in).await;
+            attempt += 1;
+        }
+    }
+
+    async fn rpc(&self, endpoint: Endpoint, params: &str) -> Result<AurResponse, AurError> {
+        parse_rpc_body(&self.rpc_body(endpoint, params).await?)
+    }
+
+    /// Performs an RPC call through the response cache and returns the raw
+    /// body. Fresh entries are returned as-is; entries past their TTL but
+    /// within `STALE_WINDOW` are returned immediately while a background
+    /// request refreshes them; and when the AUR is unreachable any cached
+    /// entry beats an error.
+    async fn rpc_body(&self, endpoint: Endpoint, params: &str) -> Result<String, AurError> {
+        let url = format!("{}/rpc?v=5&{}", self.base_url, params);
+
+        let cached = self.cache.get(&url).await;
+        if let Some(cached) = cached.as_ref().filter(|c| c.age <= endpoint.ttl() + STALE_WINDOW) {
+            if cached.age > endpoint.ttl() {
+                let client = self.clone();
+                let url = url.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = client.fetch_rpc(&url).await {
+                        eprintln!("Background refresh of {} failed: {}", url, e);
+                    }
+                });
+            }
+            return Ok(cached.body.clone());
+        }
+
+        match self.fetch_rpc(&url).await {
+            Err(e) if e.is_unreachable() => match cached {
+                Some(cached) => Ok(cached.body),
+                None => Err(e),
+            },
+            result => result,
+        }
+    }
+
+    /// Fetches an RPC URL and caches the body if it is well-formed JSON
+    /// and not an RPC error.
+    async fn fetch_rpc(&self, url: &str) -> Result<String, AurError> {
+        let response = self.send(self.http.get(url)).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(AurError::Http(status));
+        }
+
+        let body = response.text().await?;
+        serde_json::from_str::<serde_json::Value>(&body)?;
+        if let Some(e) = rpc_error(&body) {
+            return Err(e);
+        }
+        if let Err(e) = self.cache.put(url, &body).await {
+            eprintln!("Failed to cache response for {}: {}", url, e);
+        }
+
+        Ok(body)
+    }
+
+    /// Deletes cached RPC responses too old for any endpoint to serve, even
+    /// as stale. Returns how many were removed.
+    pub async fn evict_cache(&self) -> Result<usize, AurError> {
+        let longest_ttl = [Endpoint::Search, Endpoint::Info, Endpoint::Suggest]
+            .into_iter()
+            .map(Endpoint::ttl)
+            .max()
+            .unwrap_or_default();
+        Ok(self.cache.evict_older_than(longest_ttl + STALE_WINDOW).await?)
+    }
+
+    /// Drops every cached RPC response. The offline index is kept.
+    pub async fn clear_cache(&self) -> Result<(), AurError> {
+        Ok(self.cache.clear().await?)
+    }
+
+    /// Searches the RPC, falling back to the offline index when the AUR
+    /// cannot be reached.
+    pub async fn search(&self, query: &str, field: SearchField) -> Result<Vec<AurPackage>, AurError> {
+        let params = format!(
+            "type=search&by={}&arg={}",
+            field.as_param(),
+            urlencoding::encode(query)
+        );
+        match self.rpc(Endpoint::Search, &params).await {
+            Ok(response) => Ok(response.results),
+            Err(e) if e.is_unreachable() => match self.index() {
+                Some(index) => index.search(query, field),
+                None => Err(e),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn info(&self, package_name: &str) -> Result<AurPackage, AurError> {
+        let params = format!("type=info&arg={}", urlencoding::encode(package_name));
+        let results = match self.rpc(Endpoint::Info, &params).await {
+            Ok(response) => response.results,
+            Err(e) if e.is_unreachable() => match self.index() {
+                Some(index) => index.info(package_name).into_iter().collect(),
+                None => return Err(e),
+            },
+            Err(e) => return Err(e),
+        };
+        results.into_iter().next()
+            .ok_or_else(|| AurError::NotFound(package_name.to_string()))
+    }
+
+    /// Looks up many packages at once using the RPC's `arg[]` form, split
+    /// into as few requests as the URL length allows. Names the AUR does not
+    /// know are simply absent from the result.
+    pub async fn info_many(&self, package_names: &[&str]) -> Result<Vec<AurPackage>, AurError> {
+        match self.info_many_rpc(package_names).await {
+            Err(e) if e.is_unreachable() => match self.index() {
+                Some(index) => Ok(package_names.iter().filter_map(|name| index.info(name)).collect()),
+                None => Err(e),
+            },
+            result => result,
+        }
+    }
+
+    async fn info_many_rpc(&self, package_names: &[&str]) -> Result<Vec<AurPackage>, AurError> {
+        let prefix_len = format!("{}/rpc?v=5&", self.base_url).len();
+        let mut seen = HashSet::new();
+        let mut results = Vec::new();
+        let mut args = String::new();
+
+        for name in package_names {
+            if !seen.insert(*name) {
+                continue;
+            }
+
+            let arg = format!("&arg[]={}", urlencoding::encode(name));
+            if !args.is_empty() && prefix_len + "type=info".len() + args.len() + arg.len() > MAX_URL_LEN {
+                results.extend(self.rpc(Endpoint::Info, &format!("type=info{}", args)).await?.results);
+                args.clear();
+            }
+            args.push_str(&arg);
+        }
+
+        if !args.is_empty() {
+            results.extend(self.rpc(Endpoint::Info, &format!("type=info{}", args)).await?.results);
+        }
+
+        Ok(results)
+    }
+
+    /// Package names starting with `prefix`, for autocompletion.
+    pub async fn suggest(&self, prefix: &str) -> Result<Vec<String>, AurError> {
+        let params = format!("type=suggest&arg={}", urlencoding::encode(prefix));
+        Ok(serde_json::from_str(&self.rpc_body(Endpoint::Suggest, &params).await?)?)
+    }
+
+    /// Package base names starting with `prefix`, for autocompletion.
+    pub async fn suggest_pkgbase(&self, prefix: &str) -> Result<Vec<String>, AurError> {
+        let params = format!("type=suggest-pkgbase&arg={}", urlencoding::encode(prefix));
+        Ok(serde_json::from_str(&self.rpc_body(Endpoint::Suggest, &params).await?)?)
+    }
+
+    /// Most recently submitted packages, newest first.
+    pub async fn new_packages(&self) -> Result<Vec<FeedEntry>, AurError> {
+        self.feed(&self.new_packages_feed).await
+    }
+
+    /// Most recently updated packages, newest first.
+    pub async fn updated_packages(&self) -> Result<Vec<FeedEntry>, AurError> {
+        self.feed(&self.updated_packages_feed).await
+    }
+
+    async fn feed(&self, url: &str) -> Result<Vec<FeedEntry>, AurError> {
+        let body = match url.strip_prefix("file://") {
+            Some(path) => tokio::fs::read_to_string(path).await?,
+            None => {
+                let response = self.send(self.http.get(url)).await?;
+                let status = response.status();
+                if !status.is_success() {
+                    return Err(AurError::Http(status));
+                }
+                response.text().await?
+            }
+        };
+
+        rss_feed::parse(&body).map_err(AurError::Feed)
+    }
+
+    /// The offline index, if one has been loaded.
+    pub fn index(&self) -> Option<Arc<PackageIndex>> {
+        self.index.read().ok()?.clone()
+    }
+
+    /// Loads the previously downloaded metadata archive from the cache
+    /// directory. Parsing takes a while, so it happens off the main thread.
+    pub async fn load_index(&self) -> Result<(), AurError> {
+        let archive = self.cache_dir.join(INDEX_ARCHIVE);
+        let meta = read_index_meta(&self.cache_dir.join(INDEX_META)).await;
+        let updated = UNIX_EPOCH + Duration::from_secs(meta.checked_at.max(0) as u64);
+
+        let index = tokio::task::spawn_blocking(move || PackageIndex::load(&archive, updated))
+            .await
+            .map_err(|e| AurError::Io(std::io::Error::other(e)))??;
+
+        if let Ok(mut slot) = self.index.write() {
+            *slot = Some(Arc::new(index));
+        }
+        Ok(())
+    }
+
+    /// Revalidates the metadata archive with `If-None-Match` /
+    /// `If-Modified-Since`, downloads it if it changed, and reloads the index.
+    pub async fn refresh_index(&self) -> Result<(), AurError> {
+        let archive = self.cache_dir.join(INDEX_ARCHIVE);
+        let meta_path = self.cache_dir.join(INDEX_META);
+        let mut meta = read_index_meta(&meta_path).await;
+        let have_archive = tokio::fs::try_exists(&archive).await.unwrap_or(false);
+
+        let mut request = self
+            .http
+            .get(format!("{}/{}", self.base_url, INDEX_ARCHIVE))
+            .timeout(INDEX_TIMEOUT);
+        if have_archive {
+            if let Some(etag) = &meta.etag {
+                request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &meta.last_modified {
+                request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = self.send(request).await?;
+        let status = response.status();
+        let changed = if status == reqwest::StatusCode::NOT_MODIFIED {
+            false
+        } else if status.is_success() {
+            let header = |name| {
+                response
+                    .headers()
+                    .get(name)
+                    .and_then(|v: &reqwest::header::HeaderValue| v.to_str().ok())
+                    .map(str::to_string)
+            };
+            meta.etag = header(reqwest::header::ETAG);
+            meta.last_modified = header(reqwest::header::LAST_MODIFIED);
+
+            let body = response.bytes().await?;
+            crate::paths::write_atomic_async(archive.clone(), body.to_vec()).await?;
+            true
+        } else {
+            return Err(AurError::Http(status));
+        };
+
+        meta.checked_at = Timestamp::now().as_unix();
+        crate::paths::write_atomic_async(meta_path, serde_json::to_vec(&meta)?).await?;
+
+        match self.index() {
+            Some(index) if !changed => {
+                // Same data, but it is now confirmed current.
+                index.mark_updated(SystemTime::now());
+                Ok(())
+            }
+            _ => self.load_index().await,
+        }
+    }
+}
+
+/// Parses a `Retry-After` given in seconds. The HTTP-date form is rare
+/// enough that we fall back to our own backoff for it.
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+fn parse_rpc_body(body: &str) -> Result<AurResponse, AurError> {
+    if let Some(e) = rpc_error(body) {
+        return Err(e);
+    }
+    Ok(serde_json::from_str(body)?)
+}
+
+/// Picks out `{"type": "error", "error": "..."}` responses. Any other body,
+/// including the bare arrays the suggest endpoints return, passes.
+fn rpc_error(body: &str) -> Option<AurError> {
+    #[derive(Deserialize)]
+    struct ErrorResponse {
+        #[serde(rename = "type")]
+        kind: String,
+        error: Option<String>,
+    }
+
+    let response: ErrorResponse = serde_json::from_str(body).ok()?;
+    (response.kind == "error").then(|| {
+        AurError::Rpc(response.error.unwrap_or_else(|| "Unknown RPC error".to_string()))
+    })
+}
+
+async fn read_index_meta(path: &std::path::Path) -> IndexMeta {
+    match tokio::fs::read(path).await {
+        Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_default(),
+        Err(_) => IndexMeta::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Answers one HTTP request with `body` and hands back the request head.
+    fn serve_once(body: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            String::from_utf8_lossy(&request).into_owned()
+        });
+        (base_url, server)
+    }
+
+    fn test_cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aurlooker-test-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[tokio::test]
+    async fn queries_the_configured_server() {
+        let (base_url, server) = serve_once(
+            r#"{"version":5,"type":"multiinfo","resultcount":1,"results":[{"Name":"yay","PackageBase":"yay","Version":"12.3.5-1"}]}"#,
+        );
+        let cache_dir = test_cache_dir("client");
+        let client = AurClient::builder()
+            .base_url(format!("{}/", base_url))
+            .cache_dir(&cache_dir)
+            .timeout(Duration::from_secs(5))
+            .connect_timeout(Duration::from_secs(5))
+            .user_agent("aurlooker-test")
+            .build()
+            .unwrap();
+
+        let package = client.info("yay").await.unwrap();
+        assert_eq!(package.name, "yay");
+        assert_eq!(package.version, "12.3.5-1");
+
+        let request = server.join().unwrap().to_ascii_lowercase();
+        assert!(request.starts_with("get /rpc?v=5&type=info&arg=yay "), "{}", request);
+        assert!(request.contains("\r\nuser-agent: aurlooker-test\r\n"), "{}", request);
+
+        // Answered from the cache now that the server is gone
+        assert_eq!(client.info("yay").await.unwrap().version, "12.3.5-1");
+        let _ = std::fs::remove_dir_all(&cache_dir);
+    }
 }
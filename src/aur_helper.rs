@@ -0,0 +1,128 @@
+use std::path::Path;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum HelperAction {
+    Install,
+    /// Rebuild at the current AUR version, even if something is installed.
+    Update,
+    Remove,
+}
+
+/// A command-line AUR helper. Implementations only describe their
+/// arguments; `command` puts the full command line together.
+pub trait AurHelper {
+    /// Display name, which is also the executable looked up on `PATH`.
+    fn name(&self) -> &'static str;
+
+    fn install_args(&self) -> &'static [&'static str] {
+        &["-S", "--needed"]
+    }
+
+    fn update_args(&self) -> &'static [&'static str] {
+        &["-S"]
+    }
+
+    fn remove_args(&self) -> &'static [&'static str] {
+        &["-Rns"]
+    }
+
+    fn is_installed(&self) -> bool {
+        in_path(self.name())
+    }
+
+    /// The argument vector for `action` on `packages`, with the user's
+    /// `extra_flags` placed before the package names.
+    fn command(&self, action: HelperAction, packages: &[&str], extra_flags: &[String]) -> Vec<String> {
+        let action_args = match action {
+            HelperAction::Install => self.install_args(),
+            HelperAction::Update => self.update_args(),
+            HelperAction::Remove => self.remove_args(),
+        };
+        std::iter::once(self.name())
+            .chain(action_args.iter().copied())
+            .map(str::to_string)
+            .chain(extra_flags.iter().cloned())
+            .chain(packages.iter().map(|p| p.to_string()))
+            .collect()
+    }
+}
+
+pub struct Yay;
+pub struct Paru;
+pub struct Pikaur;
+pub struct Trizen;
+pub struct Aura;
+
+impl AurHelper for Yay {
+    fn name(&self) -> &'static str {
+        "yay"
+    }
+}
+
+impl AurHelper for Paru {
+    fn name(&self) -> &'static str {
+        "paru"
+    }
+}
+
+impl AurHelper for Pikaur {
+    fn name(&self) -> &'static str {
+        "pikaur"
+    }
+}
+
+impl AurHelper for Trizen {
+    fn name(&self) -> &'static str {
+        "trizen"
+    }
+}
+
+impl AurHelper for Aura {
+    fn name(&self) -> &'static str {
+        "aura"
+    }
+
+    // Aura keeps AUR operations under -A; removal passes through to pacman.
+    fn install_args(&self) -> &'static [&'static str] {
+        &["-A", "--needed"]
+    }
+
+    fn update_args(&self) -> &'static [&'static str] {
+        &["-A"]
+    }
+}
+
+/// Every supported helper, in the order they are preferred when the user
+/// has not picked one.
+pub const HELPERS: [&dyn AurHelper; 5] = [&Yay, &Paru, &Pikaur, &Trizen, &Aura];
+
+pub fn by_name(name: &str) -> Option<&'static dyn AurHelper> {
+    HELPERS.into_iter().find(|helper| helper.name() == name)
+}
+
+/// The helpers found on `PATH`.
+pub fn detect() -> Vec<&'static dyn AurHelper> {
+    HELPERS.into_iter().filter(|helper| helper.is_installed()).collect()
+}
+
+/// The preferred helper if it is installed, otherwise the first one found.
+pub fn select(preferred: Option<&str>) -> Option<&'static dyn AurHelper> {
+    preferred
+        .and_then(by_name)
+        .filter(|helper| helper.is_installed())
+        .or_else(|| detect().into_iter().next())
+}
+
+/// Whether `program` is an executable somewhere on `PATH`.
+pub fn in_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).any(|dir| is_executable(&dir.join(program))))
+        .unwrap_or(false)
+}
+
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
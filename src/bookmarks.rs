@@ -0,0 +1,140 @@
+use crate::aur_client::Timestamp;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    pub added: Timestamp,
+    #[serde(default)]
+    pub note: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Names of the collections this bookmark belongs to.
+    #[serde(default)]
+    pub collections: Vec<String>,
+}
+
+impl Bookmark {
+    pub fn new(name: &str) -> Self {
+        Bookmark {
+            name: name.to_string(),
+            added: Timestamp::now(),
+            note: String::new(),
+            tags: Vec::new(),
+            collections: Vec::new(),
+        }
+    }
+}
+
+/// The user's bookmarked packages, backed by a JSON file that is rewritten
+/// atomically on every change.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Bookmarks {
+    #[serde(default)]
+    entries: Vec<Bookmark>,
+    /// Every collection, including ones no bookmark is in yet.
+    #[serde(default)]
+    collections: Vec<String>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl Bookmarks {
+    pub fn default_path() -> PathBuf {
+        crate::paths::data_dir().join("bookmarks.json")
+    }
+
+    /// Loads bookmarks from `path`. A missing file means no bookmarks; an
+    /// unreadable one is moved aside so the next save does not destroy it.
+    pub fn load(path: &Path) -> Self {
+        let mut bookmarks = match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                eprintln!("Failed to parse {}: {}", path.display(), e);
+                let backup = path.with_extension("json.bak");
+                if let Err(e) = fs::rename(path, &backup) {
+                    eprintln!("Failed to back up {}: {}", path.display(), e);
+                }
+                Bookmarks::default()
+            }),
+            Err(_) => Bookmarks::default(),
+        };
+        bookmarks.path = path.to_path_buf();
+        bookmarks
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.iter().any(|b| b.name == name)
+    }
+
+    /// Bookmarks in the order they were added.
+    pub fn entries(&self) -> &[Bookmark] {
+        &self.entries
+    }
+
+    pub fn add(&mut self, name: &str) {
+        if self.contains(name) {
+            return;
+        }
+        self.entries.push(Bookmark::new(name));
+    }
+
+    /// Adds a bookmark from another machine, keeping its note, tags and
+    /// collections. Returns false if the package was already bookmarked.
+    pub fn import(&mut self, bookmark: Bookmark) -> bool {
+        if self.contains(&bookmark.name) {
+            return false;
+        }
+        for collection in &bookmark.collections {
+            self.add_collection(collection);
+        }
+        self.entries.push(bookmark);
+        true
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.entries.retain(|b| b.name != name);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Bookmark> {
+        self.entries.iter().find(|b| b.name == name)
+    }
+
+    /// Replaces the note, tags and collections of an existing bookmark.
+    /// Collections not seen before are created.
+    pub fn update(&mut self, name: &str, note: String, tags: Vec<String>, collections: Vec<String>) {
+        for collection in &collections {
+            self.add_collection(collection);
+        }
+        if let Some(bookmark) = self.entries.iter_mut().find(|b| b.name == name) {
+            bookmark.note = note;
+            bookmark.tags = tags;
+            bookmark.collections = collections;
+        }
+    }
+
+    pub fn collections(&self) -> &[String] {
+        &self.collections
+    }
+
+    pub fn add_collection(&mut self, collection: &str) {
+        if !collection.is_empty() && !self.collections.iter().any(|c| c == collection) {
+            self.collections.push(collection.to_string());
+        }
+    }
+
+    /// Deletes a collection. Its bookmarks stay, they just leave it.
+    pub fn remove_collection(&mut self, collection: &str) {
+        self.collections.retain(|c| c != collection);
+        for bookmark in &mut self.entries {
+            bookmark.collections.retain(|c| c != collection);
+        }
+    }
+
+    /// Writes to a temporary file next to the target and renames it over,
+    /// so a crash mid-write never leaves a truncated bookmarks file.
+    pub fn save(&self) -> std::io::Result<()> {
+        crate::paths::write_atomic(&self.path, &serde_json::to_vec_pretty(self)?)
+    }
+}
@@ -1,6 +1,147 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::aur_client::AurClient;
+use crate::aur_helper::{self, HelperAction};
+use crate::package_checker::InstalledPackage;
+use crate::settings::Settings;
+
+/// How a command run for the user ended.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandOutcome {
+    Exited(i32),
+    /// Killed by the signal with this number.
+    Signaled(i32),
+    /// The command never ran, with the reason.
+    NotStarted(String),
+}
+
+impl CommandOutcome {
+    /// Decodes a `waitpid` status.
+    pub fn from_wait_status(status: i32) -> Self {
+        use std::os::unix::process::ExitStatusExt;
+        let status = std::process::ExitStatus::from_raw(status);
+        match (status.code(), status.signal()) {
+            (Some(code), _) => CommandOutcome::Exited(code),
+            (None, Some(signal)) => CommandOutcome::Signaled(signal),
+            (None, None) => CommandOutcome::NotStarted("Unknown exit status".to_string()),
+        }
+    }
+
+    /// Decodes a status as the shell reports it in `$?`, where 128 + n
+    /// means killed by signal n.
+    pub fn from_shell_status(status: i32) -> Self {
+        if status > 128 {
+            CommandOutcome::Signaled(status - 128)
+        } else {
+            CommandOutcome::Exited(status)
+        }
+    }
+
+    pub fn success(&self) -> bool {
+        *self == CommandOutcome::Exited(0)
+    }
+}
+
+impl std::fmt::Display for CommandOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandOutcome::Exited(0) => write!(f, "Finished successfully"),
+            CommandOutcome::Exited(code) => write!(f, "Failed with exit status {}", code),
+            CommandOutcome::Signaled(signal) => write!(f, "Killed by signal {}", signal),
+            CommandOutcome::NotStarted(reason) => write!(f, "Could not start: {}", reason),
+        }
+    }
+}
+
+/// The shell command line that runs `action` on the given packages: the
+/// user's AUR helper when one is installed, otherwise pacman for removal and
+/// a native makepkg build for installs and updates. Several packages go to
+/// one invocation, so the user answers the prompts once for the whole batch.
+pub async fn action_command(
+    settings: &Settings,
+    client: &AurClient,
+    installed: &HashMap<String, InstalledPackage>,
+    action: HelperAction,
+    package_names: &[&str],
+) -> Result<String, Box<dyn std::error::Error>> {
+    if let Some(name) = package_names.iter().find(|name| !crate::manifest::is_valid_package_name(name)) {
+        return Err(format!("Invalid package name: {}", name).into());
+    }
+
+    if let Some(helper) = aur_helper::select(settings.preferred_helper.as_deref()) {
+        let args = helper.command(action, package_names, &settings.flags_for(helper.name()));
+        return Ok(quote_all(&args));
+    }
+
+    match action {
+        HelperAction::Remove => {
+            let args: Vec<String> = ["sudo", "pacman", "-Rns"]
+                .into_iter()
+                .chain(package_names.iter().copied())
+                .map(str::to_string)
+                .collect();
+            Ok(quote_all(&args))
+        }
+        HelperAction::Install | HelperAction::Update => {
+            let plan = crate::native_build::plan(client, installed, package_names).await?;
+            let script = crate::native_build::build_script(
+                &plan,
+                client.base_url(),
+                &crate::paths::cache_dir().join("build"),
+                aur_helper::in_path("git"),
+            );
+            Ok(format!("sh -c {}", shell_quote(&script)))
+        }
+    }
+}
+
+fn quote_all(args: &[String]) -> String {
+    args.iter().map(|arg| shell_quote(arg)).collect::<Vec<_>>().join(" ")
+}
+
+/// Quotes `arg` for `sh` unless it is made only of characters the shell
+/// leaves alone.
+pub fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '@' | '%' | '+' | '=' | ':' | ',' | '.' | '/' | '-' | '_'));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// Runs `command` in an external terminal emulator, which stays open until
+/// the user presses Enter. The terminal itself cannot be followed, so the
+/// command's exit status is written to the returned file once it ends (as
+/// a hangup if the terminal is closed first); see `read_exit_status`.
+pub fn spawn_in_terminal(command: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    let status_dir = crate::paths::cache_dir().join("runs");
+    fs::create_dir_all(&status_dir)?;
+    let status_file = status_dir.join(format!(
+        "{}-{}.status",
+        std::process::id(),
+        RUNS.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = fs::remove_file(&status_file);
+
+    let command = format!(
+        "status_file={}\n\
+         trap 'echo 129 > \"$status_file\"; exit 129' HUP\n\
+         {}\n\
+         echo $? > \"$status_file\"\n\
+         trap - HUP\n\
+         echo; echo 'Press Enter to close...'; read _",
+        shell_quote(&status_file.to_string_lossy()),
+        command
+    );
 
-pub fn spawn_yay_install(package_name: &str) -> Result<(), Box<dyn std::error::Error>> {
     // Detect which terminal emulator is available
     let terminals = vec![
         ("alacritty", vec!["-e", "sh", "-c"]),
@@ -11,19 +152,25 @@ pub fn spawn_yay_install(package_name: &str) -> Result<(), Box<dyn std::error::E
         ("xterm", vec!["-e", "sh", "-c"]),
     ];
 
-    let yay_command = format!("yay -S --needed {}; echo '\nPress Enter to close...'; read", package_name);
-
     for (terminal, args) in terminals {
         if Command::new("which").arg(terminal).output()?.status.success() {
             let mut cmd = Command::new(terminal);
             for arg in args {
                 cmd.arg(arg);
             }
-            cmd.arg(&yay_command);
+            cmd.arg(&command);
             cmd.spawn()?;
-            return Ok(());
+            return Ok(status_file);
         }
     }
 
     Err("No supported terminal emulator found".into())
 }
+
+/// The outcome recorded in a status file from `spawn_in_terminal`, or
+/// `None` while the command is still running. The file is removed once read.
+pub fn read_exit_status(status_file: &Path) -> Option<CommandOutcome> {
+    let status = fs::read_to_string(status_file).ok()?.trim().parse().ok()?;
+    let _ = fs::remove_file(status_file);
+    Some(CommandOutcome::from_shell_status(status))
+}
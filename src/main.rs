@@ -1,7 +1,19 @@
 mod aur_client;
+mod aur_helper;
+mod bookmarks;
 mod installer;
+mod manifest;
+mod native_build;
 mod ui;
 mod package_checker;
+mod package_index;
+mod paths;
+mod rate_limit;
+mod response_cache;
+mod rss_feed;
+mod settings;
+mod updates;
+mod version;
 
 use gtk4::prelude::*;
 use gtk4::Application;
@@ -16,14 +28,66 @@ fn main() {
 
     adw::init().expect("Failed to initialize libadwaita");
 
+    let mut client_builder = aur_client::AurClient::builder();
+    if let Ok(base_url) = std::env::var("AURLOOKER_BASE_URL") {
+        client_builder = client_builder.base_url(base_url);
+    }
+    if let Ok(feed) = std::env::var("AURLOOKER_NEW_FEED") {
+        client_builder = client_builder.new_packages_feed(feed);
+    }
+    if let Ok(feed) = std::env::var("AURLOOKER_UPDATED_FEED") {
+        client_builder = client_builder.updated_packages_feed(feed);
+    }
+    if let Ok(dir) = std::env::var("AURLOOKER_CACHE_DIR") {
+        client_builder = client_builder.cache_dir(dir);
+    }
+    if let Some(timeout) = env_seconds("AURLOOKER_TIMEOUT") {
+        client_builder = client_builder.timeout(timeout);
+    }
+    if let Some(timeout) = env_seconds("AURLOOKER_CONNECT_TIMEOUT") {
+        client_builder = client_builder.connect_timeout(timeout);
+    }
+    if let Ok(user_agent) = std::env::var("AURLOOKER_USER_AGENT") {
+        client_builder = client_builder.user_agent(user_agent);
+    }
+    if let Ok(value) = std::env::var("AURLOOKER_RATE_LIMIT") {
+        // <burst>,<requests per second>
+        let parsed = value
+            .split_once(',')
+            .and_then(|(burst, rate)| Some((burst.trim().parse().ok()?, rate.trim().parse().ok()?)));
+        match parsed {
+            Some((burst, requests_per_sec)) => {
+                client_builder = client_builder.rate_limit(burst, requests_per_sec);
+            }
+            None => eprintln!(
+                "Ignoring AURLOOKER_RATE_LIMIT={:?}: expected <burst>,<requests per second>",
+                value
+            ),
+        }
+    }
+    let client = client_builder.build().expect("Failed to create AUR client");
+
     let app = Application::builder()
         .application_id(APP_ID)
         .build();
 
-    app.connect_activate(|app| {
-        let window = ui::window::build_ui(app);
+    app.connect_activate(move |app| {
+        let window = ui::window::build_ui(app, client.clone());
         window.present();
     });
 
     app.run();
 }
+
+/// A positive number of seconds from the environment. Invalid values are
+/// reported and ignored.
+fn env_seconds(var: &str) -> Option<std::time::Duration> {
+    let value = std::env::var(var).ok()?;
+    match value.trim().parse::<f64>() {
+        Ok(secs) if secs > 0.0 && secs.is_finite() => Some(std::time::Duration::from_secs_f64(secs)),
+        _ => {
+            eprintln!("Ignoring {}={:?}: expected a positive number of seconds", var, value);
+            None
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use crate::bookmarks::Bookmark;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+const FORMAT_VERSION: u32 = 1;
+
+const BOOKMARKS_SECTION: &str = "[bookmarks]";
+const PACKAGES_SECTION: &str = "[packages]";
+
+/// What one machine wants another to have: its bookmarks and the foreign
+/// (AUR) packages it has installed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    #[serde(default = "format_version")]
+    pub version: u32,
+    #[serde(default)]
+    pub bookmarks: Vec<Bookmark>,
+    #[serde(default)]
+    pub packages: Vec<String>,
+}
+
+fn format_version() -> u32 {
+    FORMAT_VERSION
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ManifestFormat {
+    Json,
+    /// One name per line under `[bookmarks]` and `[packages]` headers.
+    /// Names before any header are packages, so `pacman -Qqm` output
+    /// imports as is.
+    Text,
+}
+
+impl ManifestFormat {
+    /// JSON for `.json` files, plain text for anything else.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => ManifestFormat::Json,
+            _ => ManifestFormat::Text,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ManifestError {
+    Json(serde_json::Error),
+    /// An entry that is not a valid package name, with its 1-based line
+    /// number in text manifests.
+    InvalidName { line: Option<usize>, name: String },
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::Json(e) => write!(f, "Invalid JSON manifest: {}", e),
+            ManifestError::InvalidName { line: Some(line), name } => {
+                write!(f, "Line {}: '{}' is not a valid package name", line, name)
+            }
+            ManifestError::InvalidName { line: None, name } => {
+                write!(f, "'{}' is not a valid package name", name)
+            }
+            ManifestError::UnsupportedVersion(version) => {
+                write!(f, "Manifest version {} is newer than this app supports", version)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+impl From<serde_json::Error> for ManifestError {
+    fn from(e: serde_json::Error) -> Self {
+        ManifestError::Json(e)
+    }
+}
+
+impl Manifest {
+    pub fn new(bookmarks: Vec<Bookmark>, packages: Vec<String>) -> Self {
+        Manifest {
+            version: FORMAT_VERSION,
+            bookmarks,
+            packages,
+        }
+    }
+
+    /// Parses a manifest in either format; JSON is recognised by its
+    /// leading `{` rather than by file name.
+    pub fn parse(contents: &str) -> Result<Self, ManifestError> {
+        let manifest = if contents.trim_start().starts_with('{') {
+            serde_json::from_str::<Manifest>(contents)?
+        } else {
+            Self::parse_text(contents)?
+        };
+
+        if manifest.version > FORMAT_VERSION {
+            return Err(ManifestError::UnsupportedVersion(manifest.version));
+        }
+        // Names end up on a shell command line when installed.
+        let mut names = manifest.bookmarks.iter().map(|b| &b.name).chain(&manifest.packages);
+        if let Some(name) = names.find(|name| !is_valid_package_name(name)) {
+            return Err(ManifestError::InvalidName { line: None, name: name.clone() });
+        }
+        Ok(manifest)
+    }
+
+    fn parse_text(contents: &str) -> Result<Self, ManifestError> {
+        let mut manifest = Manifest::new(Vec::new(), Vec::new());
+        let mut in_bookmarks = false;
+
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line.eq_ignore_ascii_case(BOOKMARKS_SECTION) {
+                in_bookmarks = true;
+                continue;
+            }
+            if line.eq_ignore_ascii_case(PACKAGES_SECTION) {
+                in_bookmarks = false;
+                continue;
+            }
+            if !is_valid_package_name(line) {
+                return Err(ManifestError::InvalidName { line: Some(index + 1), name: line.to_string() });
+            }
+
+            if in_bookmarks {
+                manifest.bookmarks.push(Bookmark::new(line));
+            } else {
+                manifest.packages.push(line.to_string());
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    pub fn serialize(&self, format: ManifestFormat) -> Result<String, ManifestError> {
+        match format {
+            ManifestFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+            ManifestFormat::Text => Ok(self.to_text()),
+        }
+    }
+
+    /// The text format keeps only names; notes, tags and collections need JSON.
+    fn to_text(&self) -> String {
+        let mut out = String::from("# aurlooker manifest\n");
+        out.push_str(BOOKMARKS_SECTION);
+        out.push('\n');
+        for bookmark in &self.bookmarks {
+            out.push_str(&bookmark.name);
+            out.push('\n');
+        }
+        out.push('\n');
+        out.push_str(PACKAGES_SECTION);
+        out.push('\n');
+        for package in &self.packages {
+            out.push_str(package);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Every distinct name in the manifest, packages first.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for name in self.packages.iter().chain(self.bookmarks.iter().map(|b| &b.name)) {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        names
+    }
+}
+
+/// Package names as makepkg allows them: alphanumerics and `@._+-`, not
+/// starting with a hyphen or dot.
+pub fn is_valid_package_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(['-', '.'])
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '@' | '.' | '_' | '+' | '-'))
+}
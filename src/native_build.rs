@@ -0,0 +1,334 @@
+use crate::aur_client::{AurClient, AurError, AurPackage};
+use crate::package_checker::InstalledPackage;
+use crate::version::Dependency;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+/// One package base to fetch, build with makepkg and install.
+#[derive(Debug, Clone)]
+pub struct BuildStep {
+    pub package_base: String,
+    /// Snapshot tarball path on the AUR, used when git is unavailable.
+    pub url_path: Option<String>,
+    /// Packages of this base that were asked for.
+    pub explicit: Vec<String>,
+    /// Packages of this base only needed by other steps, installed with
+    /// `--asdeps`. Other packages the base builds (split siblings, `-debug`
+    /// packages) are not installed.
+    pub dependencies: Vec<String>,
+}
+
+/// Package bases in build order: every AUR dependency comes before the
+/// packages that need it.
+#[derive(Debug, Clone)]
+pub struct BuildPlan {
+    pub steps: Vec<BuildStep>,
+}
+
+#[derive(Debug)]
+pub enum BuildError {
+    Aur(AurError),
+    /// A requested package is not in the AUR.
+    NotFound(String),
+    /// AUR packages that depend on each other, as a chain of package bases.
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::Aur(e) => write!(f, "{}", e),
+            BuildError::NotFound(name) => write!(f, "{} is not in the AUR", name),
+            BuildError::Cycle(bases) => write!(f, "Dependency cycle: {}", bases.join(" → ")),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+impl From<AurError> for BuildError {
+    fn from(e: AurError) -> Self {
+        BuildError::Aur(e)
+    }
+}
+
+/// Resolves `targets` and their AUR dependencies (depends, makedepends and
+/// checkdepends, recursively) into a build plan. Dependencies that are
+/// already installed are skipped; ones the AUR does not know are left to
+/// `makepkg --syncdeps` to pull from the repositories. Dependencies on names
+/// only provided by some AUR package are not resolved.
+pub async fn plan(
+    client: &AurClient,
+    installed: &HashMap<String, InstalledPackage>,
+    targets: &[&str],
+) -> Result<BuildPlan, BuildError> {
+    let mut packages: HashMap<String, AurPackage> = HashMap::new();
+    let mut queued: HashSet<String> = targets.iter().map(|t| t.to_string()).collect();
+    let mut pending: Vec<String> = queued.iter().cloned().collect();
+
+    // Breadth-first: one batched info request per level of the tree
+    while !pending.is_empty() {
+        let names: Vec<&str> = pending.iter().map(String::as_str).collect();
+        let found = client.info_many(&names).await?;
+        let mut next = Vec::new();
+        for package in found {
+            for dependency in build_dependencies(&package) {
+                if crate::package_checker::find_satisfier(installed, &dependency).is_none()
+                    && queued.insert(dependency.name.clone())
+                {
+                    next.push(dependency.name);
+                }
+            }
+            packages.insert(package.name.clone(), package);
+        }
+        pending = next;
+    }
+
+    if let Some(missing) = targets.iter().find(|target| !packages.contains_key(**target)) {
+        return Err(BuildError::NotFound(missing.to_string()));
+    }
+    order(&packages, targets)
+}
+
+fn build_dependencies(package: &AurPackage) -> impl Iterator<Item = Dependency> + '_ {
+    [&package.depends, &package.makedepends, &package.checkdepends]
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| Dependency::parse(entry))
+}
+
+/// Topologically sorts the package bases of `packages` so dependencies
+/// build first.
+fn order(packages: &HashMap<String, AurPackage>, targets: &[&str]) -> Result<BuildPlan, BuildError> {
+    let base_of = |package: &AurPackage| package.package_base.clone().unwrap_or_else(|| package.name.clone());
+
+    // Edges between package bases, ignoring dependencies inside one base
+    let mut edges: HashMap<String, Vec<String>> = HashMap::new();
+    let mut url_paths: HashMap<String, Option<String>> = HashMap::new();
+    for package in packages.values() {
+        let base = base_of(package);
+        url_paths.insert(base.clone(), package.url_path.clone());
+        let deps = edges.entry(base.clone()).or_default();
+        for dependency in build_dependencies(package) {
+            if let Some(dep_package) = packages.get(&dependency.name) {
+                let dep_base = base_of(dep_package);
+                if dep_base != base && !deps.contains(&dep_base) {
+                    deps.push(dep_base);
+                }
+            }
+        }
+    }
+
+    // Depth-first post-order; `path` holds the bases being visited, so
+    // meeting one again is a cycle. Sorted for a stable order.
+    fn visit(
+        base: &str,
+        edges: &HashMap<String, Vec<String>>,
+        done: &mut HashSet<String>,
+        path: &mut Vec<String>,
+        out: &mut Vec<String>,
+    ) -> Result<(), BuildError> {
+        if done.contains(base) {
+            return Ok(());
+        }
+        if let Some(start) = path.iter().position(|b| b == base) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(base.to_string());
+            return Err(BuildError::Cycle(cycle));
+        }
+        path.push(base.to_string());
+        let mut deps = edges.get(base).cloned().unwrap_or_default();
+        deps.sort();
+        for dep in &deps {
+            visit(dep, edges, done, path, out)?;
+        }
+        path.pop();
+        done.insert(base.to_string());
+        out.push(base.to_string());
+        Ok(())
+    }
+
+    let mut bases: Vec<&String> = edges.keys().collect();
+    bases.sort();
+    let mut done = HashSet::new();
+    let mut ordered = Vec::new();
+    for base in bases {
+        visit(base, &edges, &mut done, &mut Vec::new(), &mut ordered)?;
+    }
+
+    let steps = ordered
+        .into_iter()
+        .map(|base| {
+            let mut members: Vec<&String> = packages
+                .values()
+                .filter(|package| base_of(package) == base)
+                .map(|package| &package.name)
+                .collect();
+            members.sort();
+            let (explicit, dependencies) = members
+                .into_iter()
+                .cloned()
+                .partition(|name| targets.contains(&name.as_str()));
+            BuildStep {
+                url_path: url_paths.get(&base).cloned().flatten(),
+                package_base: base,
+                explicit,
+                dependencies,
+            }
+        })
+        .collect();
+    Ok(BuildPlan { steps })
+}
+
+/// A POSIX shell script that carries out `plan` in `work_dir`: fetch each
+/// package base (git clone or pull, or the snapshot tarball with `use_git`
+/// off), build it with `makepkg --syncdeps` and install the result with
+/// `pacman -U`. Only the packages in the plan are installed, not everything
+/// `makepkg --packagelist` reports. It stops at the first failure.
+pub fn build_script(plan: &BuildPlan, aur_base_url: &str, work_dir: &Path, use_git: bool) -> String {
+    let quote = crate::installer::shell_quote;
+    let mut script = String::from("set -e\n");
+    // Split command substitutions on newlines only, so paths may hold spaces
+    script.push_str("IFS='\n'\n");
+    // Built package files whose pkgname is one of the arguments; file names
+    // are pkgname-pkgver-pkgrel-arch.pkg.tar.*
+    script.push_str(
+        "package_files() {\n\
+         \tfor file in $(makepkg --packagelist); do\n\
+         \t\tname=${file##*/}; name=${name%-*}; name=${name%-*}; name=${name%-*}\n\
+         \t\tfor wanted in \"$@\"; do [ \"$name\" = \"$wanted\" ] && echo \"$file\"; done\n\
+         \tdone\n\
+         \treturn 0\n\
+         }\n",
+    );
+    script.push_str(&format!("mkdir -p {0}\ncd {0}\n", quote(&work_dir.to_string_lossy())));
+
+    let total = plan.steps.len();
+    for (index, step) in plan.steps.iter().enumerate() {
+        let base = quote(&step.package_base);
+        script.push_str(&format!(
+            "echo {}\n",
+            quote(&format!("==> [{}/{}] {}", index + 1, total, step.package_base))
+        ));
+
+        match (&step.url_path, use_git) {
+            (Some(url_path), false) => {
+                let url = quote(&format!("{}{}", aur_base_url, url_path));
+                script.push_str(&format!("rm -rf {0}\ncurl -fsSL {1} | tar xz\n", base, url));
+            }
+            _ => {
+                let url = quote(&format!("{}/{}.git", aur_base_url, step.package_base));
+                script.push_str(&format!(
+                    "if [ -d {0}/.git ]; then git -C {0} pull --ff-only; else git clone {1} {0}; fi\n",
+                    base, url
+                ));
+            }
+        }
+
+        let mut commands = vec!["makepkg --syncdeps --force".to_string()];
+        for (names, flags) in [(&step.explicit, ""), (&step.dependencies, " --asdeps")] {
+            if !names.is_empty() {
+                let names: Vec<String> = names.iter().map(|name| quote(name)).collect();
+                commands.push(format!("sudo pacman -U{} $(package_files {})", flags, names.join(" ")));
+            }
+        }
+        script.push_str(&format!("(cd {} && {})\n", base, commands.join(" && ")));
+    }
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(name: &str, base: &str, depends: &[&str], makedepends: &[&str]) -> AurPackage {
+        let list = |entries: &[&str]| Some(entries.iter().map(|entry| entry.to_string()).collect());
+        AurPackage {
+            name: name.to_string(),
+            package_base: Some(base.to_string()),
+            version: "1.0-1".to_string(),
+            url_path: Some(format!("/cgit/aur.git/snapshot/{}.tar.gz", base)),
+            depends: list(depends),
+            makedepends: list(makedepends),
+            ..Default::default()
+        }
+    }
+
+    fn packages(list: Vec<AurPackage>) -> HashMap<String, AurPackage> {
+        list.into_iter().map(|package| (package.name.clone(), package)).collect()
+    }
+
+    fn steps(plan: &BuildPlan) -> Vec<(&str, Vec<&str>, Vec<&str>)> {
+        fn names(names: &[String]) -> Vec<&str> {
+            names.iter().map(String::as_str).collect()
+        }
+        plan.steps
+            .iter()
+            .map(|step| (step.package_base.as_str(), names(&step.explicit), names(&step.dependencies)))
+            .collect()
+    }
+
+    #[test]
+    fn orders_bases_dependencies_first() {
+        let packages = packages(vec![
+            package("app", "app", &["libfoo>=1.0", "glibc"], &["tool"]),
+            package("tool", "tool", &["libfoo"], &[]),
+            // Split base: only dependencies outside the base count
+            package("libfoo", "libfoo", &[], &["libfoo-docs"]),
+            package("libfoo-docs", "libfoo", &[], &[]),
+        ]);
+        let plan = order(&packages, &["app", "libfoo-docs"]).unwrap();
+        assert_eq!(
+            steps(&plan),
+            [
+                ("libfoo", vec!["libfoo-docs"], vec!["libfoo"]),
+                ("tool", vec![], vec!["tool"]),
+                ("app", vec!["app"], vec![]),
+            ]
+        );
+        assert_eq!(plan.steps[0].url_path.as_deref(), Some("/cgit/aur.git/snapshot/libfoo.tar.gz"));
+    }
+
+    #[test]
+    fn reports_dependency_cycles() {
+        let packages = packages(vec![
+            package("a", "a", &["b"], &[]),
+            package("b", "b-base", &[], &["a"]),
+            package("c", "c", &[], &[]),
+        ]);
+        match order(&packages, &["a"]) {
+            Err(BuildError::Cycle(bases)) => assert_eq!(bases, ["a", "b-base", "a"]),
+            other => panic!("expected a cycle, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn script_builds_and_installs_each_step() {
+        let packages = packages(vec![
+            package("app", "app", &["libfoo"], &[]),
+            package("libfoo", "libfoo", &[], &[]),
+        ]);
+        let plan = order(&packages, &["app"]).unwrap();
+
+        let script = build_script(&plan, "https://aur.archlinux.org", Path::new("/tmp/aur builds"), true);
+        assert!(script.starts_with("set -e\n"));
+        assert!(script.contains("mkdir -p '/tmp/aur builds'\ncd '/tmp/aur builds'\n"));
+        assert!(script.contains(
+            "if [ -d libfoo/.git ]; then git -C libfoo pull --ff-only; \
+             else git clone https://aur.archlinux.org/libfoo.git libfoo; fi\n"
+        ));
+        assert!(script.contains(
+            "(cd libfoo && makepkg --syncdeps --force && sudo pacman -U --asdeps $(package_files libfoo))\n"
+        ));
+        assert!(script.contains("(cd app && makepkg --syncdeps --force && sudo pacman -U $(package_files app))\n"));
+        assert!(script.find("(cd libfoo").unwrap() < script.find("(cd app").unwrap());
+
+        let script = build_script(&plan, "https://aur.archlinux.org", Path::new("/tmp/b"), false);
+        assert!(script.contains(
+            "rm -rf app\ncurl -fsSL https://aur.archlinux.org/cgit/aur.git/snapshot/app.tar.gz | tar xz\n"
+        ));
+        assert!(!script.contains("git clone"));
+    }
+}
@@ -1,26 +1,324 @@
-use std::process::Command;
-use std::collections::HashSet;
-
-pub fn get_installed_packages() -> HashSet<String> {
-    let mut installed = HashSet::new();
-    
-    if let Ok(output) = Command::new("pacman").arg("-Qq").output() {
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                installed.insert(line.trim().to_string());
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use crate::aur_client::Timestamp;
+use crate::version::{Dependency, Version};
+
+const DEFAULT_DB_PATH: &str = "/var/lib/pacman";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallReason {
+    Explicit,
+    /// Pulled in as a dependency of another package.
+    Dependency,
+}
+
+/// One entry of pacman's local database.
+#[derive(Debug, Clone)]
+pub struct InstalledPackage {
+    pub name: String,
+    pub version: Version,
+    pub reason: InstallReason,
+    pub install_date: Option<Timestamp>,
+    pub packager: Option<String>,
+    pub provides: Vec<String>,
+    pub replaces: Vec<String>,
+    /// Not in any sync repository, which on most systems means it came
+    /// from the AUR.
+    pub foreign: bool,
+}
+
+/// pacman's database directory: `$AURLOOKER_PACMAN_DB`, defaulting to
+/// `/var/lib/pacman`.
+pub fn db_path() -> PathBuf {
+    std::env::var_os("AURLOOKER_PACMAN_DB")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_DB_PATH))
+}
+
+/// Reads every installed package from `<db_path>/local/*/desc`, keyed by
+/// name. Foreign status comes from the package names in `<db_path>/sync/*.db`.
+/// This is plain blocking file IO; call it off the main thread.
+pub fn get_installed_packages(db_path: &Path) -> io::Result<HashMap<String, InstalledPackage>> {
+    let sync_names = sync_package_names(&db_path.join("sync"));
+
+    let mut installed = HashMap::new();
+    for entry in fs::read_dir(db_path.join("local"))? {
+        let entry = entry?;
+        let desc = match fs::read_to_string(entry.path().join("desc")) {
+            Ok(desc) => desc,
+            // ALPM_DB_VERSION and other stray files
+            Err(_) => continue,
+        };
+        match parse_desc(&desc) {
+            Some(mut package) => {
+                package.foreign = !sync_names.contains(&package.name);
+                installed.insert(package.name.clone(), package);
+            }
+            None => eprintln!("Skipping malformed entry {}", entry.path().display()),
+        }
+    }
+
+    Ok(installed)
+}
+
+/// Names provided or replaced by installed packages, mapped to the package
+/// doing so, e.g. `foo` to `foo-git`. Built once per load of the database
+/// for `find_provider`; the alphabetically first package wins a name.
+pub fn provider_index(installed: &HashMap<String, InstalledPackage>) -> HashMap<String, String> {
+    let mut packages: Vec<&InstalledPackage> = installed.values().collect();
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut index = HashMap::new();
+    for package in packages {
+        for entry in package.provides.iter().chain(&package.replaces) {
+            let provided = Dependency::parse(entry).name;
+            if provided != package.name {
+                index.entry(provided).or_insert_with(|| package.name.clone());
             }
         }
     }
-    
-    installed
+    index
 }
 
-pub fn is_package_installed(package_name: &str) -> bool {
-    Command::new("pacman")
-        .arg("-Qq")
-        .arg(package_name)
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
+/// Another installed package that stands in for `name` by providing or
+/// replacing it, e.g. `foo-git` for `foo`. `providers` is the
+/// `provider_index` of `installed`.
+pub fn find_provider<'a>(
+    installed: &'a HashMap<String, InstalledPackage>,
+    providers: &HashMap<String, String>,
+    name: &str,
+) -> Option<&'a InstalledPackage> {
+    providers.get(name).and_then(|provider| installed.get(provider))
+}
+
+/// The installed package that fulfils `dependency`: one with its name and
+/// an allowed version, or one providing it. An unversioned provide only
+/// satisfies an unversioned dependency, as in pacman.
+pub fn find_satisfier<'a>(
+    installed: &'a HashMap<String, InstalledPackage>,
+    dependency: &Dependency,
+) -> Option<&'a InstalledPackage> {
+    let by_name = installed
+        .get(&dependency.name)
+        .filter(|package| dependency.allows(&package.version));
+    by_name.or_else(|| installed.values().find(|package| {
+        package.provides.iter().any(|entry| {
+            let provided = Dependency::parse(entry);
+            provided.name == dependency.name
+                && match &provided.constraint {
+                    Some((_, version)) => dependency.allows(version),
+                    None => dependency.constraint.is_none(),
+                }
+        })
+    }))
+}
+
+/// `get_installed_packages` on a blocking thread. A database that cannot
+/// be read is logged and treated as empty.
+pub async fn load_installed_packages() -> HashMap<String, InstalledPackage> {
+    let path = db_path();
+    let result = tokio::task::spawn_blocking(move || get_installed_packages(&path)).await;
+    match result {
+        Ok(Ok(installed)) => installed,
+        Ok(Err(e)) => {
+            eprintln!("Failed to read the pacman database at {}: {}", db_path().display(), e);
+            HashMap::new()
+        }
+        Err(e) => {
+            eprintln!("Failed to read the pacman database: {}", e);
+            HashMap::new()
+        }
+    }
+}
+
+/// Parses a local `desc` file: `%FIELD%` headers each followed by one value
+/// per line, sections separated by blank lines.
+fn parse_desc(desc: &str) -> Option<InstalledPackage> {
+    let mut fields: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut current = None;
+    for line in desc.lines() {
+        let line = line.trim_end();
+        if line.len() > 2 && line.starts_with('%') && line.ends_with('%') {
+            current = Some(&line[1..line.len() - 1]);
+        } else if line.is_empty() {
+            current = None;
+        } else if let Some(field) = current {
+            fields.entry(field).or_default().push(line);
+        }
+    }
+
+    let single = |field: &str| fields.get(field).and_then(|values| values.first()).copied();
+    let list = |field: &str| {
+        fields
+            .get(field)
+            .map(|values| values.iter().map(|v| v.to_string()).collect())
+            .unwrap_or_default()
+    };
+
+    Some(InstalledPackage {
+        name: single("NAME")?.to_string(),
+        version: Version::parse(single("VERSION")?),
+        reason: match single("REASON") {
+            Some("1") => InstallReason::Dependency,
+            _ => InstallReason::Explicit,
+        },
+        install_date: single("INSTALLDATE")
+            .and_then(|date| date.parse().ok())
+            .map(Timestamp),
+        packager: single("PACKAGER").map(str::to_string),
+        provides: list("PROVIDES"),
+        replaces: list("REPLACES"),
+        foreign: false,
+    })
+}
+
+/// Names of every package in the sync databases under `sync_dir`. A
+/// database that cannot be read is skipped, which makes its packages look
+/// foreign.
+fn sync_package_names(sync_dir: &Path) -> HashSet<String> {
+    let mut names = HashSet::new();
+    let entries = match fs::read_dir(sync_dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", sync_dir.display(), e);
+            return names;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("db") {
+            continue;
+        }
+        match read_sync_db(&path) {
+            Ok(db_names) => names.extend(db_names),
+            Err(e) => eprintln!("Failed to read sync database {}: {}", path.display(), e),
+        }
+    }
+    names
+}
+
+/// A sync database is a gzipped tar with one `name-pkgver-pkgrel/`
+/// directory per package; the names are recovered from those paths.
+fn read_sync_db(path: &Path) -> io::Result<HashSet<String>> {
+    let mut archive = Vec::new();
+    flate2::read::GzDecoder::new(fs::File::open(path)?).read_to_end(&mut archive)?;
+
+    let names = tar_entry_paths(&archive)
+        .into_iter()
+        .filter_map(|entry| {
+            let dir = entry.split('/').next()?;
+            // Drop pkgrel, then pkgver
+            let mut parts = dir.rsplitn(3, '-');
+            parts.next()?;
+            parts.next()?;
+            parts.next().map(str::to_string)
+        })
+        .collect();
+    Ok(names)
+}
+
+/// Paths of the entries in an uncompressed tar archive. Only what sync
+/// databases use is supported: ustar headers with short names.
+fn tar_entry_paths(archive: &[u8]) -> Vec<String> {
+    const BLOCK: usize = 512;
+    let field = |bytes: &[u8]| {
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        String::from_utf8_lossy(&bytes[..end]).into_owned()
+    };
+
+    let mut paths = Vec::new();
+    let mut offset = 0;
+    while offset + BLOCK <= archive.len() {
+        let header = &archive[offset..offset + BLOCK];
+        // Two zero blocks end the archive
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+
+        let size = usize::from_str_radix(field(&header[124..136]).trim(), 8).unwrap_or(0);
+        let name = field(&header[0..100]);
+        let prefix = if &header[257..262] == b"ustar" {
+            field(&header[345..500])
+        } else {
+            String::new()
+        };
+        // Regular files and directories; skip pax and GNU metadata entries
+        if matches!(header[156], b'0' | b'5' | 0) {
+            paths.push(if prefix.is_empty() { name } else { format!("{}/{}", prefix, name) });
+        }
+
+        offset += BLOCK + size.div_ceil(BLOCK) * BLOCK;
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_db() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/pacman")
+    }
+
+    #[test]
+    fn parses_desc() {
+        let desc = fs::read_to_string(fixture_db().join("local/foo-git-r10.abc123-1/desc")).unwrap();
+        let package = parse_desc(&desc).unwrap();
+        assert_eq!(package.name, "foo-git");
+        assert_eq!(package.version.to_string(), "1:r10.abc123-1");
+        assert_eq!(package.reason, InstallReason::Dependency);
+        assert_eq!(package.install_date.map(|date| date.as_unix()), Some(1712000000));
+        assert_eq!(package.packager, None);
+        assert_eq!(package.provides, ["foo=1.0", "libfoo.so"]);
+        assert_eq!(package.replaces, ["foo-old"]);
+    }
+
+    #[test]
+    fn rejects_desc_without_name_or_version() {
+        assert!(parse_desc("%NAME%\nfoo\n\n").is_none());
+        assert!(parse_desc("%VERSION%\n1.0-1\n\n").is_none());
+    }
+
+    #[test]
+    fn reads_sync_db_names() {
+        let names = read_sync_db(&fixture_db().join("sync/core.db")).unwrap();
+        assert_eq!(names, HashSet::from(["glibc".to_string(), "gcc-libs".to_string()]));
+    }
+
+    #[test]
+    fn reads_installed_packages() {
+        let installed = get_installed_packages(&fixture_db()).unwrap();
+        let mut names: Vec<&str> = installed.keys().map(String::as_str).collect();
+        names.sort();
+        assert_eq!(names, ["foo-git", "glibc", "yay"]);
+
+        let glibc = &installed["glibc"];
+        assert!(!glibc.foreign);
+        assert_eq!(glibc.packager.as_deref(), Some("Arch Linux Maintainers <maintainers@archlinux.org>"));
+        let yay = &installed["yay"];
+        assert!(yay.foreign);
+        assert_eq!(yay.reason, InstallReason::Explicit);
+        assert!(installed["foo-git"].foreign);
+    }
+
+    #[test]
+    fn finds_providers_and_satisfiers() {
+        let installed = get_installed_packages(&fixture_db()).unwrap();
+        let providers = provider_index(&installed);
+        assert_eq!(providers.get("libc.so").map(String::as_str), Some("glibc"));
+        assert_eq!(find_provider(&installed, &providers, "foo").map(|p| p.name.as_str()), Some("foo-git"));
+        assert_eq!(find_provider(&installed, &providers, "foo-old").map(|p| p.name.as_str()), Some("foo-git"));
+        assert!(find_provider(&installed, &providers, "yay").is_none());
+
+        let satisfier = |entry: &str| find_satisfier(&installed, &Dependency::parse(entry)).map(|p| p.name.clone());
+        assert_eq!(satisfier("glibc>=2.39").as_deref(), Some("glibc"));
+        assert_eq!(satisfier("foo>=1.0").as_deref(), Some("foo-git"));
+        assert_eq!(satisfier("foo>1.0"), None);
+        assert_eq!(satisfier("libfoo.so").as_deref(), Some("foo-git"));
+        // An unversioned provide does not satisfy a versioned dependency
+        assert_eq!(satisfier("libfoo.so>=1"), None);
+    }
 }
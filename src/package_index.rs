@@ -0,0 +1,117 @@
+use crate::aur_client::{AurError, AurPackage, SearchField};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// The RPC's `max_rpc_results`.
+const MAX_RESULTS: usize = 5000;
+
+/// In-memory copy of the AUR's `packages-meta-ext-v1.json.gz` archive,
+/// searchable the same way as the RPC.
+pub struct PackageIndex {
+    packages: Vec<AurPackage>,
+    by_name: HashMap<String, usize>,
+    updated: Mutex<SystemTime>,
+}
+
+impl PackageIndex {
+    /// Reads and decompresses a downloaded archive. `updated` is when the
+    /// archive was last confirmed current with the server.
+    pub fn load(archive: &Path, updated: SystemTime) -> Result<Self, AurError> {
+        let file = File::open(archive)?;
+        let decoder = flate2::read::GzDecoder::new(BufReader::new(file));
+        let packages: Vec<AurPackage> = serde_json::from_reader(BufReader::new(decoder))?;
+
+        let by_name = packages
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.name.clone(), i))
+            .collect();
+
+        Ok(Self {
+            packages,
+            by_name,
+            updated: Mutex::new(updated),
+        })
+    }
+
+    pub fn package_count(&self) -> usize {
+        self.packages.len()
+    }
+
+    pub fn age(&self) -> Duration {
+        self.updated
+            .lock()
+            .map(|updated| updated.elapsed().unwrap_or_default())
+            .unwrap_or_default()
+    }
+
+    /// Records that the server confirmed this data is still current.
+    pub fn mark_updated(&self, when: SystemTime) {
+        if let Ok(mut updated) = self.updated.lock() {
+            *updated = when;
+        }
+    }
+
+    pub fn info(&self, name: &str) -> Option<AurPackage> {
+        self.by_name.get(name).map(|&i| self.packages[i].clone())
+    }
+
+    /// Mirrors the RPC's matching rules: name searches are case-insensitive
+    /// substring matches, everything else must match a whole entry. Like the
+    /// RPC, queries matching more than `MAX_RESULTS` packages are rejected.
+    pub fn search(&self, query: &str, field: SearchField) -> Result<Vec<AurPackage>, AurError> {
+        let needle = query.to_lowercase();
+        let mut found = self.packages.iter().filter(|p| matches(p, &needle, query, field));
+        let results: Vec<AurPackage> = found.by_ref().take(MAX_RESULTS).cloned().collect();
+        if found.next().is_some() {
+            return Err(AurError::Rpc("Too many package results.".to_string()));
+        }
+        Ok(results)
+    }
+}
+
+fn matches(package: &AurPackage, needle: &str, query: &str, field: SearchField) -> bool {
+    let list_has = |list: &Option<Vec<String>>| {
+        list.iter().flatten().any(|entry| entry == query)
+    };
+    let deps_have = |list: &Option<Vec<String>>| {
+        list.iter().flatten().any(|entry| dependency_name(entry) == query)
+    };
+
+    match field {
+        SearchField::Name => package.name.to_lowercase().contains(needle),
+        SearchField::NameDesc => {
+            package.name.to_lowercase().contains(needle)
+                || package
+                    .description
+                    .as_ref()
+                    .is_some_and(|d| d.to_lowercase().contains(needle))
+        }
+        SearchField::Maintainer => package.maintainer.as_deref() == Some(query),
+        SearchField::Submitter => package.submitter.as_deref() == Some(query),
+        SearchField::CoMaintainers => list_has(&package.comaintainers),
+        SearchField::Depends => deps_have(&package.depends),
+        SearchField::MakeDepends => deps_have(&package.makedepends),
+        SearchField::OptDepends => deps_have(&package.optdepends),
+        SearchField::CheckDepends => deps_have(&package.checkdepends),
+        SearchField::Provides => package.name == query || deps_have(&package.provides),
+        SearchField::Conflicts => deps_have(&package.conflicts),
+        SearchField::Replaces => deps_have(&package.replaces),
+        SearchField::Keywords => list_has(&package.keywords),
+        SearchField::Groups => list_has(&package.groups),
+    }
+}
+
+/// Strips version constraints and optdepends descriptions: `foo>=1.2` and
+/// `foo: for bar support` both become `foo`.
+fn dependency_name(entry: &str) -> &str {
+    entry
+        .split(['<', '>', '=', ':'])
+        .next()
+        .unwrap_or(entry)
+        .trim()
+}
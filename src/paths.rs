@@ -0,0 +1,54 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+const APP_DIR: &str = "aurlooker";
+
+/// `$XDG_CACHE_HOME/aurlooker`, falling back to `~/.cache/aurlooker`.
+pub fn cache_dir() -> PathBuf {
+    xdg_dir("XDG_CACHE_HOME", ".cache")
+}
+
+/// `$XDG_DATA_HOME/aurlooker`, falling back to `~/.local/share/aurlooker`.
+pub fn data_dir() -> PathBuf {
+    xdg_dir("XDG_DATA_HOME", ".local/share")
+}
+
+/// `$XDG_CONFIG_HOME/aurlooker`, falling back to `~/.config/aurlooker`.
+pub fn config_dir() -> PathBuf {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// Writes to a temporary file next to `path` and renames it over, so a
+/// crash mid-write never leaves a truncated file. Creates the parent
+/// directory if needed.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+    let partial = PathBuf::from(partial);
+    let mut file = fs::File::create(&partial)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    fs::rename(&partial, path)
+}
+
+/// `write_atomic` on a blocking thread, for async code.
+pub async fn write_atomic_async(path: PathBuf, bytes: Vec<u8>) -> io::Result<()> {
+    tokio::task::spawn_blocking(move || write_atomic(&path, &bytes))
+        .await
+        .map_err(io::Error::other)?
+}
+
+fn xdg_dir(var: &str, home_fallback: &str) -> PathBuf {
+    // The spec says relative values must be ignored.
+    std::env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(home_fallback)))
+        .unwrap_or_else(std::env::temp_dir)
+        .join(APP_DIR)
+}
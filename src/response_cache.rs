@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Raw response bodies on disk, one file per request URL. Freshness rules
+/// are left to the caller; this only remembers when each body was stored.
+#[derive(Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+}
+
+pub struct CachedResponse {
+    pub body: String,
+    pub age: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    key: String,
+    stored_at: u64,
+    body: String,
+}
+
+impl ResponseCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub async fn get(&self, key: &str) -> Option<CachedResponse> {
+        let bytes = tokio::fs::read(self.path_for(key)).await.ok()?;
+        let entry: Entry = serde_json::from_slice(&bytes).ok()?;
+        // Guard against the (unlikely) hash collision.
+        if entry.key != key {
+            return None;
+        }
+
+        let stored_at = UNIX_EPOCH + Duration::from_secs(entry.stored_at);
+        Some(CachedResponse {
+            body: entry.body,
+            age: stored_at.elapsed().unwrap_or_default(),
+        })
+    }
+
+    /// Stores `body` under `key`, replacing any previous entry atomically.
+    pub async fn put(&self, key: &str, body: &str) -> std::io::Result<()> {
+        let entry = Entry {
+            key: key.to_string(),
+            stored_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            body: body.to_string(),
+        };
+
+        crate::paths::write_atomic_async(self.path_for(key), serde_json::to_vec(&entry)?).await
+    }
+
+    pub async fn clear(&self) -> std::io::Result<()> {
+        match tokio::fs::remove_dir_all(&self.dir).await {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Deletes entries stored more than `max_age` ago, going by file
+    /// modification time, along with leftovers of interrupted writes.
+    /// Returns how many files were removed.
+    pub async fn evict_older_than(&self, max_age: Duration) -> std::io::Result<usize> {
+        let mut dir = match tokio::fs::read_dir(&self.dir).await {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            result => result?,
+        };
+
+        let mut removed = 0;
+        while let Some(file) = dir.next_entry().await? {
+            let path = file.path();
+            let expired = match path.extension().and_then(|ext| ext.to_str()) {
+                Some("json") => file
+                    .metadata()
+                    .await?
+                    .modified()?
+                    .elapsed()
+                    .is_ok_and(|age| age > max_age),
+                Some("part") => true,
+                _ => false,
+            };
+            if expired {
+                tokio::fs::remove_file(&path).await?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", fnv1a(key.as_bytes())))
+    }
+}
+
+/// FNV-1a, used because file names must stay stable across builds (unlike
+/// `DefaultHasher`).
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn evicts_old_entries() {
+        let dir = std::env::temp_dir().join(format!("aurlooker-test-cache-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let cache = ResponseCache::new(dir.clone());
+        cache.put("old", "1").await.unwrap();
+        cache.put("new", "2").await.unwrap();
+        std::fs::write(dir.join("0123456789abcdef.json.part"), "").unwrap();
+
+        let two_days_ago = SystemTime::now() - Duration::from_secs(2 * 24 * 60 * 60);
+        std::fs::File::options()
+            .write(true)
+            .open(cache.path_for("old"))
+            .unwrap()
+            .set_modified(two_days_ago)
+            .unwrap();
+
+        assert_eq!(cache.evict_older_than(Duration::from_secs(24 * 60 * 60)).await.unwrap(), 2);
+        assert!(cache.get("old").await.is_none());
+        assert_eq!(cache.get("new").await.unwrap().body, "2");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
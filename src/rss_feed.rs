@@ -0,0 +1,131 @@
+/// One `<item>` of the AUR's `/rss/` or `/rss/modified` feed.
+#[derive(Debug, Clone)]
+pub struct FeedEntry {
+    /// The item title, which the AUR sets to the package name.
+    pub name: String,
+}
+
+/// Parses an RSS 2.0 document into its items. Only item titles are looked
+/// at; anything else is ignored.
+pub fn parse(xml: &str) -> Result<Vec<FeedEntry>, String> {
+    if !xml.contains("<rss") {
+        return Err("Not an RSS document".to_string());
+    }
+
+    let mut entries = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find("<item>").or_else(|| rest.find("<item ")) {
+        let item_start = &rest[start..];
+        let end = item_start
+            .find("</item>")
+            .ok_or_else(|| "Unterminated <item> element".to_string())?;
+        let item = &item_start[..end];
+
+        let name = element_text(item, "title")
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| "Feed item without a title".to_string())?;
+        entries.push(FeedEntry { name });
+
+        rest = &item_start[end + "</item>".len()..];
+    }
+
+    Ok(entries)
+}
+
+fn element_text(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+
+    let mut search_from = 0;
+    let start = loop {
+        let pos = search_from + xml[search_from..].find(&open)?;
+        let after = &xml[pos + open.len()..];
+        // Make sure we matched `<title>` and not `<titlefoo>`.
+        if after.starts_with(['>', ' ', '/']) {
+            break pos;
+        }
+        search_from = pos + open.len();
+    };
+
+    let content_start = start + xml[start..].find('>')? + 1;
+    if xml[start..content_start].ends_with("/>") {
+        return Some(String::new());
+    }
+    let content_end = content_start + xml[content_start..].find(&close)?;
+    Some(decode_text(xml[content_start..content_end].trim()))
+}
+
+fn decode_text(raw: &str) -> String {
+    if let Some(cdata) = raw.strip_prefix("<![CDATA[").and_then(|r| r.strip_suffix("]]>")) {
+        return cdata.to_string();
+    }
+
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';') else { break };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn parses_feed_items_in_order() {
+        let feed = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/rss/modified.xml");
+        let entries = parse(&std::fs::read_to_string(feed).unwrap()).unwrap();
+        let names: Vec<&str> = entries.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(names, ["yay", "foo-git", "python-bar"]);
+    }
+
+    #[test]
+    fn decodes_entities() {
+        assert_eq!(decode_text("a &amp; b &lt;c&gt; &#65;&#x42; &bogus; &"), "a & b <c> AB &bogus; &");
+        assert_eq!(decode_text("<![CDATA[a &amp; b]]>"), "a &amp; b");
+    }
+
+    #[test]
+    fn matches_whole_element_names() {
+        let item = "<titles>no</titles><title>yes</title><empty/>";
+        assert_eq!(element_text(item, "title").as_deref(), Some("yes"));
+        assert_eq!(element_text(item, "empty").as_deref(), Some(""));
+        assert_eq!(element_text(item, "link"), None);
+    }
+
+    #[test]
+    fn rejects_malformed_feeds() {
+        assert!(parse("<html></html>").is_err());
+        assert!(parse("<rss><channel><item><title>yay</title>").is_err());
+        assert!(parse("<rss><channel><item><link>x</link></item></channel></rss>").is_err());
+        assert!(parse("<rss><channel></channel></rss>").unwrap().is_empty());
+    }
+}
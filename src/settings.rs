@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// User preferences, stored as JSON under the config directory and
+/// rewritten atomically like the bookmarks file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Settings {
+    /// Name of the AUR helper to use; `None` picks the first one installed.
+    #[serde(default)]
+    pub preferred_helper: Option<String>,
+    /// Extra command-line flags per helper name, as typed by the user.
+    #[serde(default)]
+    pub helper_flags: HashMap<String, String>,
+    /// Run installs in an external terminal emulator instead of the
+    /// built-in one.
+    #[serde(default)]
+    pub external_terminal: bool,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl Settings {
+    pub fn default_path() -> PathBuf {
+        crate::paths::config_dir().join("settings.json")
+    }
+
+    /// Loads settings from `path`, falling back to defaults if the file is
+    /// missing or unreadable.
+    pub fn load(path: &Path) -> Self {
+        let mut settings = match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                eprintln!("Failed to parse {}: {}", path.display(), e);
+                Settings::default()
+            }),
+            Err(_) => Settings::default(),
+        };
+        settings.path = path.to_path_buf();
+        settings
+    }
+
+    /// The extra flags for `helper`, split into arguments.
+    pub fn flags_for(&self, helper: &str) -> Vec<String> {
+        self.helper_flags
+            .get(helper)
+            .map(|flags| flags.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        crate::paths::write_atomic(&self.path, &serde_json::to_vec_pretty(self)?)
+    }
+}
@@ -0,0 +1,162 @@
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, CheckButton, Entry, Label, Orientation};
+use super::package_list::ListContext;
+use std::rc::Rc;
+
+/// Dialog for a bookmark's note, tags and collections. `on_saved` runs after
+/// the change has been written to disk.
+pub fn show_bookmark_editor(ctx: &ListContext, name: &str, on_saved: impl Fn() + 'static) {
+    let Some(bookmark) = ctx.bookmarks.borrow().get(name).cloned() else {
+        return;
+    };
+    let all_collections = ctx.bookmarks.borrow().collections().to_vec();
+
+    let dialog = gtk4::Window::builder()
+        .title(format!("Edit Bookmark: {}", name))
+        .modal(true)
+        .default_width(450)
+        .default_height(450)
+        .build();
+
+    let main_box = GtkBox::new(Orientation::Vertical, 10);
+    main_box.set_margin_top(20);
+    main_box.set_margin_bottom(20);
+    main_box.set_margin_start(20);
+    main_box.set_margin_end(20);
+
+    // Note
+    main_box.append(&section_label("Note"));
+    let note_view = gtk4::TextView::new();
+    note_view.set_wrap_mode(gtk4::WrapMode::WordChar);
+    note_view.buffer().set_text(&bookmark.note);
+    let note_scrolled = gtk4::ScrolledWindow::builder()
+        .min_content_height(80)
+        .child(&note_view)
+        .build();
+    note_scrolled.add_css_class("frame");
+    main_box.append(&note_scrolled);
+
+    // Tags
+    main_box.append(&section_label("Tags"));
+    let tags_entry = Entry::builder()
+        .placeholder_text("Comma-separated, e.g. gpu, needs-review")
+        .text(bookmark.tags.join(", "))
+        .build();
+    main_box.append(&tags_entry);
+
+    // Collections
+    main_box.append(&section_label("Collections"));
+    let collections_box = GtkBox::new(Orientation::Vertical, 5);
+    let collection_checks: Rc<std::cell::RefCell<Vec<CheckButton>>> = Rc::default();
+    for collection in &all_collections {
+        let check = CheckButton::with_label(collection);
+        check.set_active(bookmark.collections.contains(collection));
+        collections_box.append(&check);
+        collection_checks.borrow_mut().push(check);
+    }
+    let collections_scrolled = gtk4::ScrolledWindow::builder()
+        .vexpand(true)
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .child(&collections_box)
+        .build();
+    main_box.append(&collections_scrolled);
+
+    let new_collection_box = GtkBox::new(Orientation::Horizontal, 5);
+    let new_collection_entry = Entry::builder()
+        .placeholder_text("New collection, e.g. build server")
+        .hexpand(true)
+        .build();
+    let add_collection_btn = Button::with_label("Add");
+    new_collection_box.append(&new_collection_entry);
+    new_collection_box.append(&add_collection_btn);
+    main_box.append(&new_collection_box);
+
+    let add_collection = {
+        let collections_box = collections_box.clone();
+        let collection_checks = collection_checks.clone();
+        move |entry: &Entry| {
+            let collection = entry.text().trim().to_string();
+            if collection.is_empty() {
+                return;
+            }
+            let existing = collection_checks
+                .borrow()
+                .iter()
+                .find(|check| check.label().as_deref() == Some(collection.as_str()))
+                .cloned();
+            match existing {
+                Some(check) => check.set_active(true),
+                None => {
+                    let check = CheckButton::with_label(&collection);
+                    check.set_active(true);
+                    collections_box.append(&check);
+                    collection_checks.borrow_mut().push(check);
+                }
+            }
+            entry.set_text("");
+        }
+    };
+    let add_collection = Rc::new(add_collection);
+    let add_collection_clone = add_collection.clone();
+    new_collection_entry.connect_activate(move |entry| add_collection_clone(entry));
+    let new_collection_entry_clone = new_collection_entry.clone();
+    add_collection_btn.connect_clicked(move |_| add_collection(&new_collection_entry_clone));
+
+    // Buttons
+    let button_box = GtkBox::new(Orientation::Horizontal, 10);
+    button_box.set_halign(gtk4::Align::End);
+    let cancel_btn = Button::with_label("Cancel");
+    let save_btn = Button::with_label("Save");
+    save_btn.add_css_class("suggested-action");
+    button_box.append(&cancel_btn);
+    button_box.append(&save_btn);
+    main_box.append(&button_box);
+
+    let dialog_clone = dialog.clone();
+    cancel_btn.connect_clicked(move |_| {
+        dialog_clone.close();
+    });
+
+    let dialog_clone = dialog.clone();
+    let bookmarks = ctx.bookmarks.clone();
+    let name = name.to_string();
+    save_btn.connect_clicked(move |_| {
+        let buffer = note_view.buffer();
+        let note = buffer
+            .text(&buffer.start_iter(), &buffer.end_iter(), false)
+            .trim()
+            .to_string();
+        let tags = tags_entry
+            .text()
+            .split(',')
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        let collections = collection_checks
+            .borrow()
+            .iter()
+            .filter(|check| check.is_active())
+            .filter_map(|check| check.label().map(|label| label.to_string()))
+            .collect();
+
+        {
+            let mut bookmarks = bookmarks.borrow_mut();
+            bookmarks.update(&name, note, tags, collections);
+            if let Err(e) = bookmarks.save() {
+                eprintln!("Failed to save bookmarks: {}", e);
+            }
+        }
+        on_saved();
+        dialog_clone.close();
+    });
+
+    dialog.set_child(Some(&main_box));
+    dialog.present();
+}
+
+fn section_label(text: &str) -> Label {
+    let label = Label::new(Some(text));
+    label.add_css_class("heading");
+    label.set_halign(gtk4::Align::Start);
+    label
+}
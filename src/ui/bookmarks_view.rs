@@ -0,0 +1,252 @@
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, DropDown, Label, ListBox, Orientation};
+use crate::aur_client::{AurError, AurPackage};
+use super::package_list::ListContext;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+const ALL_COLLECTIONS: &str = "All Collections";
+const EMPTY_TEXT: &str = "No bookmarks yet. Tick the box next to a package to save it here.";
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BookmarkSort {
+    DateAdded,
+    Name,
+    Popularity,
+    Votes,
+    LastModified,
+}
+
+impl BookmarkSort {
+    const ALL: [BookmarkSort; 5] = [
+        BookmarkSort::DateAdded,
+        BookmarkSort::Name,
+        BookmarkSort::Popularity,
+        BookmarkSort::Votes,
+        BookmarkSort::LastModified,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            BookmarkSort::DateAdded => "Date Bookmarked",
+            BookmarkSort::Name => "Alphabetical",
+            BookmarkSort::Popularity => "Popularity",
+            BookmarkSort::Votes => "Votes",
+            BookmarkSort::LastModified => "Last Modified",
+        }
+    }
+}
+
+/// The bookmarks page. Bookmarked names are resolved with one batched info
+/// lookup, independent of whatever search was last run, then filtered by
+/// collection and sorted with the page's own selectors.
+#[derive(Clone)]
+pub struct BookmarksView {
+    pub widget: GtkBox,
+    sort_dropdown: DropDown,
+    collection_dropdown: DropDown,
+    collection_model: gtk4::StringList,
+    delete_collection_button: Button,
+    list: ListBox,
+    empty_label: Label,
+    ctx: ListContext,
+    /// Last lookup result; bookmarks missing from it are gone from the AUR.
+    packages: Rc<RefCell<HashMap<String, AurPackage>>>,
+}
+
+impl BookmarksView {
+    pub fn new(ctx: &ListContext) -> Self {
+        let widget = GtkBox::new(Orientation::Vertical, 0);
+
+        let controls = GtkBox::new(Orientation::Horizontal, 10);
+        controls.set_margin_top(10);
+        controls.set_margin_start(15);
+        controls.set_margin_end(10);
+        let title = Label::new(Some("Bookmarks"));
+        title.add_css_class("title-2");
+        title.set_halign(gtk4::Align::Start);
+        title.set_hexpand(true);
+        controls.append(&title);
+        controls.append(&Label::new(Some("Collection:")));
+        let collection_model = gtk4::StringList::new(&[ALL_COLLECTIONS]);
+        let collection_dropdown = DropDown::new(
+            Some(collection_model.clone()),
+            None::<gtk4::Expression>,
+        );
+        controls.append(&collection_dropdown);
+        let delete_collection_button = Button::with_label("Delete Collection");
+        delete_collection_button.set_tooltip_text(Some("Delete the selected collection; its bookmarks are kept"));
+        delete_collection_button.set_sensitive(false);
+        controls.append(&delete_collection_button);
+        controls.append(&Label::new(Some("Sort:")));
+        let labels: Vec<&str> = BookmarkSort::ALL.iter().map(|s| s.label()).collect();
+        let sort_dropdown = DropDown::new(
+            Some(gtk4::StringList::new(&labels)),
+            None::<gtk4::Expression>,
+        );
+        sort_dropdown.set_selected(0);
+        controls.append(&sort_dropdown);
+        widget.append(&controls);
+
+        let empty_label = Label::new(Some(EMPTY_TEXT));
+        empty_label.add_css_class("dim-label");
+        empty_label.set_margin_top(40);
+        widget.append(&empty_label);
+
+        let scrolled = gtk4::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vexpand(true)
+            .build();
+        let list = super::package_list::create_package_list();
+        scrolled.set_child(Some(&list));
+        widget.append(&scrolled);
+
+        let view = BookmarksView {
+            widget,
+            sort_dropdown,
+            collection_dropdown,
+            collection_model,
+            delete_collection_button,
+            list,
+            empty_label,
+            ctx: ctx.clone(),
+            packages: Rc::new(RefCell::new(HashMap::new())),
+        };
+
+        let view_clone = view.clone();
+        view.sort_dropdown.connect_selected_notify(move |_| view_clone.render());
+        let view_clone = view.clone();
+        view.collection_dropdown.connect_selected_notify(move |_| view_clone.render());
+
+        let view_clone = view.clone();
+        view.delete_collection_button.connect_clicked(move |_| {
+            let Some(collection) = view_clone.selected_collection() else {
+                return;
+            };
+            {
+                let mut bookmarks = view_clone.ctx.bookmarks.borrow_mut();
+                bookmarks.remove_collection(&collection);
+                if let Err(e) = bookmarks.save() {
+                    eprintln!("Failed to save bookmarks: {}", e);
+                }
+            }
+            view_clone.collection_dropdown.set_selected(0);
+            view_clone.sync_collections();
+            view_clone.render();
+        });
+
+        view
+    }
+
+    /// `None` when "All Collections" is selected.
+    fn selected_collection(&self) -> Option<String> {
+        match self.collection_dropdown.selected() {
+            0 | gtk4::INVALID_LIST_POSITION => None,
+            index => self.collection_model.string(index).map(|s| s.to_string()),
+        }
+    }
+
+    /// Rebuilds the collection selector from the saved collections, keeping
+    /// the current selection when it still exists.
+    fn sync_collections(&self) {
+        let selected = self.selected_collection();
+        let collections = self.ctx.bookmarks.borrow().collections().to_vec();
+        let names: Vec<&str> = collections.iter().map(String::as_str).collect();
+        self.collection_model.splice(1, self.collection_model.n_items() - 1, &names);
+
+        let position = selected
+            .and_then(|selected| collections.iter().position(|c| *c == selected))
+            .map(|index| index as u32 + 1)
+            .unwrap_or(0);
+        self.collection_dropdown.set_selected(position);
+    }
+
+    /// Looks up every bookmarked package and redraws the page.
+    pub async fn load(&self) -> Result<(), AurError> {
+        let names: Vec<String> = self
+            .ctx
+            .bookmarks
+            .borrow()
+            .entries()
+            .iter()
+            .map(|b| b.name.clone())
+            .collect();
+        let name_refs: Vec<&str> = names.iter().map(String::as_str).collect();
+
+        let packages = self.ctx.client.info_many(&name_refs).await?;
+        *self.packages.borrow_mut() = packages.into_iter().map(|p| (p.name.clone(), p)).collect();
+        self.sync_collections();
+        self.render();
+        Ok(())
+    }
+
+    fn render(&self) {
+        while let Some(child) = self.list.first_child() {
+            self.list.remove(&child);
+        }
+        super::package_list::prune_row_refreshers(&self.ctx);
+
+        let sort = BookmarkSort::ALL
+            .get(self.sort_dropdown.selected() as usize)
+            .copied()
+            .unwrap_or(BookmarkSort::DateAdded);
+        let collection = self.selected_collection();
+        self.delete_collection_button.set_sensitive(collection.is_some());
+        let mut entries: Vec<_> = self
+            .ctx
+            .bookmarks
+            .borrow()
+            .entries()
+            .iter()
+            .filter(|b| match &collection {
+                Some(c) => b.collections.contains(c),
+                None => true,
+            })
+            .cloned()
+            .collect();
+        let packages = self.packages.borrow();
+
+        match sort {
+            // Newest bookmark first
+            BookmarkSort::DateAdded => entries.sort_by(|a, b| b.added.cmp(&a.added)),
+            BookmarkSort::Name => entries.sort_by(|a, b| a.name.cmp(&b.name)),
+            BookmarkSort::Popularity => entries.sort_by(|a, b| {
+                let popularity = |name: &str| packages.get(name).and_then(|p| p.popularity).unwrap_or(-1.0);
+                popularity(&b.name).total_cmp(&popularity(&a.name))
+            }),
+            BookmarkSort::Votes => entries.sort_by_key(|b| {
+                std::cmp::Reverse(packages.get(&b.name).map(|p| p.votes.unwrap_or(0)))
+            }),
+            BookmarkSort::LastModified => entries.sort_by_key(|b| {
+                std::cmp::Reverse(packages.get(&b.name).and_then(|p| p.last_modified))
+            }),
+        }
+
+        self.empty_label.set_text(if collection.is_some() {
+            "No bookmarks in this collection. Use ✎ on a bookmarked package to add it."
+        } else {
+            EMPTY_TEXT
+        });
+        self.empty_label.set_visible(entries.is_empty());
+        for entry in entries {
+            let row = match packages.get(&entry.name) {
+                Some(package) => {
+                    // Collections may have been added, or the bookmark moved
+                    // out of the one shown
+                    let view = self.clone();
+                    super::package_list::create_package_row(package.clone(), &self.ctx, move || {
+                        view.sync_collections();
+                        view.render();
+                    })
+                }
+                None => {
+                    let view = self.clone();
+                    super::package_list::create_missing_row(&entry.name, &self.ctx, move || view.render())
+                }
+            };
+            self.list.append(&row);
+        }
+    }
+}
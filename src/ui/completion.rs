@@ -0,0 +1,252 @@
+use gtk4::prelude::*;
+use gtk4::{gdk, Entry, Label, ListBox, Popover, SelectionMode};
+use crate::aur_client::AurClient;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+/// Quiet period after the last keystroke before suggestions are fetched.
+const SUGGEST_DEBOUNCE: Duration = Duration::from_millis(150);
+const MIN_PREFIX_LEN: usize = 2;
+
+#[derive(Clone, Debug)]
+pub enum Suggestion {
+    Package(String),
+    /// A package base that is not itself a package name (split packages).
+    PackageBase(String),
+}
+
+/// Completion popover for the search entry, fed by the RPC's suggest
+/// endpoints. Focus stays in the entry: Up/Down move the highlight,
+/// Enter picks it and Escape closes the popover.
+#[derive(Clone)]
+pub struct Completion {
+    entry: Entry,
+    popover: Popover,
+    list: ListBox,
+    items: Rc<RefCell<Vec<Suggestion>>>,
+    enabled: Rc<Cell<bool>>,
+    generation: Rc<Cell<u64>>,
+    pending: Rc<RefCell<Option<glib::SourceId>>>,
+    changed_handler: Rc<RefCell<Option<glib::SignalHandlerId>>>,
+}
+
+impl Completion {
+    pub fn attach(
+        entry: &Entry,
+        client: &AurClient,
+        on_choose: impl Fn(&Completion, Suggestion) + 'static,
+    ) -> Self {
+        let list = ListBox::new();
+        list.set_selection_mode(SelectionMode::Single);
+        let scrolled = gtk4::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .propagate_natural_height(true)
+            .max_content_height(320)
+            .child(&list)
+            .build();
+
+        let popover = Popover::new();
+        popover.set_child(Some(&scrolled));
+        popover.set_parent(entry);
+        popover.set_autohide(false);
+        popover.set_has_arrow(false);
+        popover.set_position(gtk4::PositionType::Bottom);
+        let popover_clone = popover.clone();
+        entry.connect_destroy(move |_| popover_clone.unparent());
+
+        let completion = Completion {
+            entry: entry.clone(),
+            popover,
+            list,
+            items: Rc::new(RefCell::new(Vec::new())),
+            enabled: Rc::new(Cell::new(true)),
+            generation: Rc::new(Cell::new(0)),
+            pending: Rc::new(RefCell::new(None)),
+            changed_handler: Rc::new(RefCell::new(None)),
+        };
+
+        let on_choose = Rc::new(on_choose);
+
+        // Fetch suggestions as the user types
+        let this = completion.clone();
+        let client = client.clone();
+        let handler = entry.connect_changed(move |entry| {
+            this.schedule(&client, entry.text().trim().to_string());
+        });
+        *completion.changed_handler.borrow_mut() = Some(handler);
+
+        // Keyboard navigation; capture phase so we see Enter before the
+        // entry turns it into `activate`.
+        let key_controller = gtk4::EventControllerKey::new();
+        key_controller.set_propagation_phase(gtk4::PropagationPhase::Capture);
+        let this = completion.clone();
+        let on_choose_clone = on_choose.clone();
+        key_controller.connect_key_pressed(move |_, key, _, _| {
+            if !this.popover.is_visible() {
+                return glib::Propagation::Proceed;
+            }
+            match key {
+                gdk::Key::Down => {
+                    this.move_selection(1);
+                    glib::Propagation::Stop
+                }
+                gdk::Key::Up => {
+                    this.move_selection(-1);
+                    glib::Propagation::Stop
+                }
+                gdk::Key::Escape => {
+                    this.hide();
+                    glib::Propagation::Stop
+                }
+                gdk::Key::Return | gdk::Key::KP_Enter => match this.selected() {
+                    Some(suggestion) => {
+                        this.hide();
+                        on_choose_clone(&this, suggestion);
+                        glib::Propagation::Stop
+                    }
+                    None => {
+                        this.hide();
+                        glib::Propagation::Proceed
+                    }
+                },
+                _ => glib::Propagation::Proceed,
+            }
+        });
+        entry.add_controller(key_controller);
+
+        // Mouse selection
+        let this = completion.clone();
+        completion.list.connect_row_activated(move |_, row| {
+            let suggestion = this.items.borrow().get(row.index() as usize).cloned();
+            if let Some(suggestion) = suggestion {
+                this.hide();
+                on_choose(&this, suggestion);
+            }
+        });
+
+        completion
+    }
+
+    /// Suggestions only make sense for name searches; other fields turn
+    /// them off.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.set(enabled);
+        if !enabled {
+            self.hide();
+        }
+    }
+
+    /// Replaces the entry text without triggering a new round of suggestions.
+    pub fn set_entry_text(&self, text: &str) {
+        let handler = self.changed_handler.borrow();
+        if let Some(handler) = handler.as_ref() {
+            self.entry.block_signal(handler);
+        }
+        self.entry.set_text(text);
+        self.entry.set_position(-1);
+        if let Some(handler) = handler.as_ref() {
+            self.entry.unblock_signal(handler);
+        }
+    }
+
+    pub fn hide(&self) {
+        self.cancel_pending();
+        self.generation.set(self.generation.get() + 1);
+        self.popover.popdown();
+    }
+
+    fn schedule(&self, client: &AurClient, prefix: String) {
+        self.hide();
+        if !self.enabled.get() || prefix.chars().count() < MIN_PREFIX_LEN {
+            return;
+        }
+
+        let this = self.clone();
+        let client = client.clone();
+        let source = glib::timeout_add_local_once(SUGGEST_DEBOUNCE, move || {
+            this.pending.borrow_mut().take();
+            this.fetch(client, prefix);
+        });
+        *self.pending.borrow_mut() = Some(source);
+    }
+
+    fn cancel_pending(&self) {
+        if let Some(source) = self.pending.borrow_mut().take() {
+            source.remove();
+        }
+    }
+
+    fn fetch(&self, client: AurClient, prefix: String) {
+        let generation = self.generation.get();
+        let this = self.clone();
+        glib::MainContext::default().spawn_local(async move {
+            let (names, bases) = tokio::join!(client.suggest(&prefix), client.suggest_pkgbase(&prefix));
+            if this.generation.get() != generation {
+                return;
+            }
+
+            let names = names.unwrap_or_else(|e| {
+                eprintln!("Suggest failed: {}", e);
+                Vec::new()
+            });
+            let bases = bases.unwrap_or_default();
+            let mut items: Vec<Suggestion> = names.iter().cloned().map(Suggestion::Package).collect();
+            items.extend(
+                bases
+                    .into_iter()
+                    .filter(|base| !names.contains(base))
+                    .map(Suggestion::PackageBase),
+            );
+            this.show(items);
+        });
+    }
+
+    fn show(&self, items: Vec<Suggestion>) {
+        while let Some(child) = self.list.first_child() {
+            self.list.remove(&child);
+        }
+
+        for item in &items {
+            let text = match item {
+                Suggestion::Package(name) => name.clone(),
+                Suggestion::PackageBase(base) => format!("{} (package base)", base),
+            };
+            let label = Label::new(Some(&text));
+            label.set_halign(gtk4::Align::Start);
+            label.set_margin_start(6);
+            label.set_margin_end(6);
+            self.list.append(&label);
+        }
+
+        let empty = items.is_empty();
+        *self.items.borrow_mut() = items;
+        if empty {
+            self.popover.popdown();
+        } else {
+            self.list.unselect_all();
+            self.popover.set_size_request(self.entry.width(), -1);
+            self.popover.popup();
+        }
+    }
+
+    fn move_selection(&self, step: i32) {
+        let count = self.items.borrow().len() as i32;
+        if count == 0 {
+            return;
+        }
+        let next = match self.list.selected_row() {
+            Some(row) => (row.index() + step).rem_euclid(count),
+            None if step > 0 => 0,
+            None => count - 1,
+        };
+        if let Some(row) = self.list.row_at_index(next) {
+            self.list.select_row(Some(&row));
+        }
+    }
+
+    fn selected(&self) -> Option<Suggestion> {
+        let row = self.list.selected_row()?;
+        self.items.borrow().get(row.index() as usize).cloned()
+    }
+}
@@ -0,0 +1,160 @@
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, DropDown, Entry, Label, Orientation};
+use crate::aur_helper::{self, HELPERS};
+use super::package_list::ListContext;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+const AUTOMATIC: &str = "Automatic (first installed)";
+
+/// Dialog for picking the AUR helper, the extra flags passed to each and
+/// where installs run.
+pub fn show_helper_settings(parent: &gtk4::Window, ctx: &ListContext) {
+    let dialog = gtk4::Window::builder()
+        .title("AUR Helper")
+        .modal(true)
+        .transient_for(parent)
+        .default_width(450)
+        .build();
+
+    let main_box = GtkBox::new(Orientation::Vertical, 10);
+    main_box.set_margin_top(20);
+    main_box.set_margin_bottom(20);
+    main_box.set_margin_start(20);
+    main_box.set_margin_end(20);
+
+    // Helper choice; entry 0 is automatic selection
+    let heading = Label::new(Some("Preferred helper"));
+    heading.add_css_class("heading");
+    heading.set_halign(gtk4::Align::Start);
+    main_box.append(&heading);
+
+    let mut labels = vec![AUTOMATIC.to_string()];
+    labels.extend(HELPERS.iter().map(|helper| {
+        if helper.is_installed() {
+            helper.name().to_string()
+        } else {
+            format!("{} (not installed)", helper.name())
+        }
+    }));
+    let label_refs: Vec<&str> = labels.iter().map(String::as_str).collect();
+    let helper_dropdown = DropDown::new(
+        Some(gtk4::StringList::new(&label_refs)),
+        None::<gtk4::Expression>,
+    );
+    let settings = ctx.settings.borrow();
+    let selected = settings
+        .preferred_helper
+        .as_deref()
+        .and_then(|name| HELPERS.iter().position(|helper| helper.name() == name))
+        .map(|index| index as u32 + 1)
+        .unwrap_or(0);
+    helper_dropdown.set_selected(selected);
+    main_box.append(&helper_dropdown);
+
+    let active_label = Label::new(None);
+    active_label.add_css_class("dim-label");
+    active_label.set_halign(gtk4::Align::Start);
+    active_label.set_wrap(true);
+    active_label.set_xalign(0.0);
+    main_box.append(&active_label);
+
+    // Extra flags for the helper shown in the dropdown (or the one
+    // automatic selection lands on)
+    let flags_heading = Label::new(None);
+    flags_heading.add_css_class("heading");
+    flags_heading.set_halign(gtk4::Align::Start);
+    flags_heading.set_margin_top(10);
+    main_box.append(&flags_heading);
+    let flags_entry = Entry::builder()
+        .placeholder_text("e.g. --noconfirm --skipreview")
+        .build();
+    main_box.append(&flags_entry);
+
+    let external_check = gtk4::CheckButton::with_label("Run in an external terminal instead of the built-in one");
+    external_check.set_active(settings.external_terminal);
+    external_check.set_margin_top(10);
+    main_box.append(&external_check);
+
+    // Edits are kept per helper until Save
+    let flags: Rc<RefCell<HashMap<String, String>>> = Rc::new(RefCell::new(settings.helper_flags.clone()));
+    drop(settings);
+    let shown_helper: Rc<RefCell<Option<&'static str>>> = Rc::new(RefCell::new(None));
+
+    let show_selected = {
+        let flags = flags.clone();
+        let shown_helper = shown_helper.clone();
+        let flags_entry = flags_entry.clone();
+        let flags_heading = flags_heading.clone();
+        let active_label = active_label.clone();
+        move |dropdown: &DropDown| {
+            let preferred = selected_helper(dropdown);
+            let helper = aur_helper::select(preferred);
+            active_label.set_text(&match (preferred, helper) {
+                (_, None) => "No supported AUR helper is installed; packages will be built with makepkg.".to_string(),
+                (Some(name), Some(helper)) if helper.name() != name => {
+                    format!("{} is not installed; {} will be used.", name, helper.name())
+                }
+                (_, Some(helper)) => format!("{} will be used.", helper.name()),
+            });
+
+            let name = preferred.or(helper.map(|helper| helper.name()));
+            *shown_helper.borrow_mut() = name;
+            flags_heading.set_text(&format!("Extra flags for {}", name.unwrap_or("the helper")));
+            flags_entry.set_sensitive(name.is_some());
+            flags_entry.set_text(name.and_then(|name| flags.borrow().get(name).cloned()).as_deref().unwrap_or(""));
+        }
+    };
+    show_selected(&helper_dropdown);
+    helper_dropdown.connect_selected_notify(move |dropdown| show_selected(dropdown));
+
+    let flags_clone = flags.clone();
+    let shown_helper_clone = shown_helper.clone();
+    flags_entry.connect_changed(move |entry| {
+        if let Some(name) = *shown_helper_clone.borrow() {
+            flags_clone.borrow_mut().insert(name.to_string(), entry.text().trim().to_string());
+        }
+    });
+
+    // Buttons
+    let button_box = GtkBox::new(Orientation::Horizontal, 10);
+    button_box.set_halign(gtk4::Align::End);
+    button_box.set_margin_top(10);
+    let cancel_btn = Button::with_label("Cancel");
+    let save_btn = Button::with_label("Save");
+    save_btn.add_css_class("suggested-action");
+    button_box.append(&cancel_btn);
+    button_box.append(&save_btn);
+    main_box.append(&button_box);
+
+    let dialog_clone = dialog.clone();
+    cancel_btn.connect_clicked(move |_| {
+        dialog_clone.close();
+    });
+
+    let dialog_clone = dialog.clone();
+    let settings = ctx.settings.clone();
+    save_btn.connect_clicked(move |_| {
+        let mut settings = settings.borrow_mut();
+        settings.preferred_helper = selected_helper(&helper_dropdown).map(str::to_string);
+        settings.helper_flags = flags.borrow().clone();
+        settings.helper_flags.retain(|_, flags| !flags.is_empty());
+        settings.external_terminal = external_check.is_active();
+        if let Err(e) = settings.save() {
+            eprintln!("Failed to save settings: {}", e);
+        }
+        dialog_clone.close();
+    });
+
+    dialog.set_child(Some(&main_box));
+    dialog.present();
+}
+
+/// `None` when automatic selection is chosen.
+fn selected_helper(dropdown: &DropDown) -> Option<&'static str> {
+    match dropdown.selected() {
+        0 | gtk4::INVALID_LIST_POSITION => None,
+        index => HELPERS.get(index as usize - 1).map(|helper| helper.name()),
+    }
+}
@@ -0,0 +1,256 @@
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, CheckButton, Label, Orientation};
+use libadwaita as adw;
+use crate::aur_helper::HelperAction;
+use crate::manifest::{Manifest, ManifestFormat};
+use super::package_list::ListContext;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// Asks where to save, then writes the bookmarks and installed foreign
+/// packages there. The file extension picks the format.
+pub async fn export_manifest(window: &gtk4::Window, ctx: &ListContext, toasts: &adw::ToastOverlay) {
+    let dialog = gtk4::FileDialog::builder()
+        .title("Export Manifest")
+        .initial_name("aurlooker-manifest.json")
+        .build();
+    let file = match dialog.save_future(Some(window)).await {
+        Ok(file) => file,
+        Err(e) => {
+            if !e.matches(gtk4::DialogError::Dismissed) {
+                eprintln!("Export dialog failed: {}", e);
+            }
+            return;
+        }
+    };
+    let Some(path) = file.path() else {
+        toasts.add_toast(adw::Toast::new("Only local files can be exported to"));
+        return;
+    };
+
+    let mut packages: Vec<String> = ctx
+        .installed
+        .borrow()
+        .values()
+        .filter(|p| p.foreign)
+        .map(|p| p.name.clone())
+        .collect();
+    packages.sort();
+    let bookmarks = ctx.bookmarks.borrow().entries().to_vec();
+    let manifest = Manifest::new(bookmarks, packages);
+
+    let result = manifest
+        .serialize(ManifestFormat::from_path(&path))
+        .map_err(|e| e.to_string())
+        .and_then(|contents| std::fs::write(&path, contents).map_err(|e| e.to_string()));
+    let message = match result {
+        Ok(()) => format!(
+            "Exported {} bookmarks and {} packages",
+            manifest.bookmarks.len(),
+            manifest.packages.len()
+        ),
+        Err(e) => format!("Failed to export manifest: {}", e),
+    };
+    toasts.add_toast(adw::Toast::new(&message));
+}
+
+/// Asks for a manifest file and shows what it contains next to what this
+/// machine has.
+pub async fn import_manifest(window: &gtk4::Window, ctx: &ListContext, toasts: &adw::ToastOverlay) {
+    let dialog = gtk4::FileDialog::builder()
+        .title("Import Manifest")
+        .build();
+    let file = match dialog.open_future(Some(window)).await {
+        Ok(file) => file,
+        Err(e) => {
+            if !e.matches(gtk4::DialogError::Dismissed) {
+                eprintln!("Import dialog failed: {}", e);
+            }
+            return;
+        }
+    };
+    let Some(path) = file.path() else {
+        toasts.add_toast(adw::Toast::new("Only local files can be imported"));
+        return;
+    };
+
+    let manifest = std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|contents| Manifest::parse(&contents).map_err(|e| e.to_string()));
+    match manifest {
+        Ok(manifest) => show_import_dialog(window, ctx, toasts, manifest),
+        Err(e) => toasts.add_toast(adw::Toast::new(&format!("Failed to import manifest: {}", e))),
+    }
+}
+
+fn show_import_dialog(parent: &gtk4::Window, ctx: &ListContext, toasts: &adw::ToastOverlay, manifest: Manifest) {
+    let dialog = gtk4::Window::builder()
+        .title("Import Manifest")
+        .modal(true)
+        .transient_for(parent)
+        .default_width(600)
+        .default_height(500)
+        .build();
+
+    let main_box = GtkBox::new(Orientation::Vertical, 10);
+    main_box.set_margin_top(20);
+    main_box.set_margin_bottom(20);
+    main_box.set_margin_start(20);
+    main_box.set_margin_end(20);
+
+    let names = manifest.names();
+    let header_label = Label::new(Some(&format!(
+        "{} bookmarks, {} packages",
+        manifest.bookmarks.len(),
+        manifest.packages.len()
+    )));
+    header_label.add_css_class("title-2");
+    header_label.set_halign(gtk4::Align::Start);
+    main_box.append(&header_label);
+
+    let status_label = Label::new(Some("Looking up packages..."));
+    status_label.add_css_class("dim-label");
+    status_label.set_halign(gtk4::Align::Start);
+    main_box.append(&status_label);
+
+    let list = super::package_list::create_package_list();
+    let scrolled = gtk4::ScrolledWindow::builder()
+        .vexpand(true)
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .child(&list)
+        .build();
+    main_box.append(&scrolled);
+
+    // Buttons
+    let button_box = GtkBox::new(Orientation::Horizontal, 10);
+    button_box.set_halign(gtk4::Align::End);
+    let close_btn = Button::with_label("Close");
+    let new_bookmarks: Vec<_> = manifest
+        .bookmarks
+        .iter()
+        .filter(|b| !ctx.bookmarks.borrow().contains(&b.name))
+        .cloned()
+        .collect();
+    let bookmarks_btn = Button::with_label(&format!("Add {} Bookmarks", new_bookmarks.len()));
+    bookmarks_btn.set_sensitive(!new_bookmarks.is_empty());
+    let install_btn = Button::with_label("Install Missing");
+    install_btn.add_css_class("suggested-action");
+    install_btn.set_sensitive(false);
+    button_box.append(&close_btn);
+    button_box.append(&bookmarks_btn);
+    button_box.append(&install_btn);
+    main_box.append(&button_box);
+
+    let dialog_clone = dialog.clone();
+    close_btn.connect_clicked(move |_| {
+        dialog_clone.close();
+    });
+
+    let bookmarks = ctx.bookmarks.clone();
+    let toasts_clone = toasts.clone();
+    bookmarks_btn.connect_clicked(move |btn| {
+        let mut bookmarks = bookmarks.borrow_mut();
+        let mut added = 0;
+        for bookmark in &new_bookmarks {
+            if bookmarks.import(bookmark.clone()) {
+                added += 1;
+            }
+        }
+        if let Err(e) = bookmarks.save() {
+            eprintln!("Failed to save bookmarks: {}", e);
+        }
+        toasts_clone.add_toast(adw::Toast::new(&format!("Added {} bookmarks", added)));
+        btn.set_sensitive(false);
+    });
+
+    // Resolve every entry against the AUR, then list them with their local
+    // state; missing ones start ticked for the batch install.
+    let checks: Rc<std::cell::RefCell<Vec<(String, CheckButton)>>> = Rc::default();
+    let ctx_clone = ctx.clone();
+    let checks_clone = checks.clone();
+    let install_btn_clone = install_btn.clone();
+    let bookmarked: HashSet<String> = manifest.bookmarks.iter().map(|b| b.name.clone()).collect();
+    glib::MainContext::default().spawn_local(async move {
+        let name_refs: Vec<&str> = names.iter().map(String::as_str).collect();
+        let (in_aur, lookup_error): (HashSet<String>, _) = match ctx_clone.client.info_many(&name_refs).await {
+            Ok(packages) => (packages.into_iter().map(|p| p.name).collect(), None),
+            Err(e) => (names.iter().cloned().collect(), Some(e)),
+        };
+
+        let mut missing = 0;
+        for name in &names {
+            let installed = ctx_clone.installed.borrow().contains_key(name);
+            let row = GtkBox::new(Orientation::Horizontal, 10);
+            row.set_margin_top(5);
+            row.set_margin_bottom(5);
+
+            let check = CheckButton::new();
+            let available = !installed && in_aur.contains(name);
+            check.set_active(available);
+            check.set_sensitive(available);
+            row.append(&check);
+
+            let name_label = Label::new(Some(name));
+            name_label.set_halign(gtk4::Align::Start);
+            name_label.set_hexpand(true);
+            row.append(&name_label);
+
+            if bookmarked.contains(name) {
+                let bookmark_label = Label::new(Some("★"));
+                bookmark_label.set_tooltip_text(Some("Bookmarked in the manifest"));
+                row.append(&bookmark_label);
+            }
+
+            let state_label = if installed {
+                let label = Label::new(Some("✓ Installed"));
+                label.add_css_class("success");
+                label
+            } else if in_aur.contains(name) {
+                missing += 1;
+                let label = Label::new(Some("Missing"));
+                label.add_css_class("accent");
+                label
+            } else {
+                let label = Label::new(Some("Not in AUR"));
+                label.add_css_class("warning");
+                label
+            };
+            row.append(&state_label);
+
+            list.append(&row);
+            if available {
+                checks_clone.borrow_mut().push((name.clone(), check));
+            }
+        }
+
+        status_label.set_text(&match lookup_error {
+            Some(e) => format!("Could not check the AUR ({}); assuming every entry exists", e),
+            None => format!("{} of {} entries are missing here", missing, names.len()),
+        });
+        install_btn_clone.set_sensitive(missing > 0);
+    });
+
+    let toasts_clone = toasts.clone();
+    let ctx = ctx.clone();
+    install_btn.connect_clicked(move |_| {
+        let selected: Vec<String> = checks
+            .borrow()
+            .iter()
+            .filter(|(_, check)| check.is_active())
+            .map(|(name, _)| name.clone())
+            .collect();
+        if selected.is_empty() {
+            return;
+        }
+        let toasts = toasts_clone.clone();
+        super::package_list::run_action(&ctx, HelperAction::Install, selected, move |outcome| {
+            if !outcome.success() {
+                eprintln!("Installation failed: {}", outcome);
+                toasts.add_toast(adw::Toast::new(&format!("Installation: {}", outcome)));
+            }
+        });
+    });
+
+    dialog.set_child(Some(&main_box));
+    dialog.present();
+}
@@ -1,3 +1,10 @@
 pub mod window;
 pub mod package_list;
+pub mod completion;
+pub mod bookmarks_view;
 
+pub mod bookmark_editor;
+pub mod manifest_dialog;
+pub mod updates_view;
+pub mod helper_settings;
+pub mod terminal_view;
@@ -1,9 +1,52 @@
 use gtk4::prelude::*;
 use gtk4::{Box as GtkBox, Button, Label, ListBox, Orientation, SelectionMode, CheckButton};
-use crate::aur_client::AurPackage;
-use std::collections::HashSet;
+use crate::aur_client::{AurClient, AurPackage, Timestamp};
+use crate::aur_helper::HelperAction;
+use crate::bookmarks::{Bookmark, Bookmarks};
+use crate::installer::CommandOutcome;
+use crate::package_checker::{InstallReason, InstalledPackage};
+use crate::settings::Settings;
+use crate::version::{Dependency, Version};
+use std::collections::HashMap;
 use std::cell::RefCell;
+use std::path::PathBuf;
 use std::rc::Rc;
+use std::time::Duration;
+
+/// How often an external terminal's status file is checked.
+const EXIT_STATUS_POLL: Duration = Duration::from_secs(1);
+
+/// State shared by every package row: the AUR client, what is installed,
+/// the bookmark list, the user's settings and the full-info results fetched
+/// for the details view.
+#[derive(Clone)]
+pub struct ListContext {
+    pub client: AurClient,
+    /// Installed packages by name; empty until the pacman database is read.
+    pub installed: Rc<RefCell<HashMap<String, InstalledPackage>>>,
+    /// `provider_index` of `installed`; kept in step by `set_installed`.
+    pub providers: Rc<RefCell<HashMap<String, String>>>,
+    pub bookmarks: Rc<RefCell<Bookmarks>>,
+    pub settings: Rc<RefCell<Settings>>,
+    pub details_cache: Rc<RefCell<HashMap<String, AurPackage>>>,
+    /// One per package row, to update its installed marker.
+    pub row_refreshers: Rc<RefCell<Vec<RowRefresher>>>,
+}
+
+impl ListContext {
+    /// Replaces the installed packages after the pacman database was read.
+    pub fn set_installed(&self, installed: HashMap<String, InstalledPackage>) {
+        *self.providers.borrow_mut() = crate::package_checker::provider_index(&installed);
+        *self.installed.borrow_mut() = installed;
+    }
+}
+
+/// Keeps a package row's installed marker in step with `ListContext::installed`.
+/// The row's widgets are held weakly so dropped rows can be pruned.
+pub struct RowRefresher {
+    package: AurPackage,
+    widgets: WeakInstallWidgets,
+}
 
 pub fn create_package_list() -> ListBox {
     let list_box = ListBox::new();
@@ -15,28 +58,26 @@ pub fn create_package_list() -> ListBox {
     list_box
 }
 
-pub fn update_package_list(
-    list_box: &ListBox,
-    packages: Vec<AurPackage>,
-    installed: &Rc<HashSet<String>>,
-    bookmarks: &Rc<RefCell<Vec<String>>>,
-) {
+pub fn update_package_list(list_box: &ListBox, packages: Vec<AurPackage>, ctx: &ListContext) {
     // Clear existing items
     while let Some(child) = list_box.first_child() {
         list_box.remove(&child);
     }
+    prune_row_refreshers(ctx);
 
     // Add new packages
     for package in packages {
-        let row = create_package_row(package, installed, bookmarks);
+        let row = create_package_row(package, ctx, || {});
         list_box.append(&row);
     }
 }
 
-fn create_package_row(
+/// Row for one package. `on_bookmark_saved` runs after the row's bookmark
+/// has been edited.
+pub fn create_package_row(
     package: AurPackage,
-    installed: &Rc<HashSet<String>>,
-    bookmarks: &Rc<RefCell<Vec<String>>>,
+    ctx: &ListContext,
+    on_bookmark_saved: impl Fn() + 'static,
 ) -> GtkBox {
     let row_box = GtkBox::new(Orientation::Horizontal, 10);
     row_box.set_margin_top(5);
@@ -44,44 +85,69 @@ fn create_package_row(
     row_box.set_margin_start(5);
     row_box.set_margin_end(5);
 
-    let is_installed = installed.contains(&package.name);
-    let is_bookmarked = bookmarks.borrow().contains(&package.name);
+    let is_bookmarked = ctx.bookmarks.borrow().contains(&package.name);
 
     // Bookmark checkbox
     let bookmark_check = CheckButton::new();
     bookmark_check.set_active(is_bookmarked);
     bookmark_check.set_tooltip_text(Some("Bookmark this package"));
     
+    row_box.append(&bookmark_check);
+
+    // Note, tags and collections of the bookmark, shown under the description
+    let bookmark_label = Label::new(None);
+    bookmark_label.set_halign(gtk4::Align::Start);
+    bookmark_label.set_wrap(true);
+    bookmark_label.set_xalign(0.0);
+    bookmark_label.add_css_class("dim-label");
+    update_bookmark_label(&bookmark_label, ctx.bookmarks.borrow().get(&package.name));
+
+    // Edit bookmark button, only useful once the package is bookmarked
+    let edit_button = Button::with_label("✎");
+    edit_button.set_tooltip_text(Some("Edit note, tags and collections"));
+    edit_button.set_valign(gtk4::Align::Center);
+    edit_button.add_css_class("flat");
+    edit_button.set_visible(is_bookmarked);
+    let pkg_name_for_edit = package.name.clone();
+    let ctx_for_edit = ctx.clone();
+    let bookmark_label_clone = bookmark_label.clone();
+    let on_bookmark_saved = Rc::new(on_bookmark_saved);
+    edit_button.connect_clicked(move |_| {
+        let ctx = ctx_for_edit.clone();
+        let name = pkg_name_for_edit.clone();
+        let label = bookmark_label_clone.clone();
+        let on_bookmark_saved = on_bookmark_saved.clone();
+        super::bookmark_editor::show_bookmark_editor(&ctx_for_edit, &pkg_name_for_edit, move || {
+            update_bookmark_label(&label, ctx.bookmarks.borrow().get(&name));
+            on_bookmark_saved();
+        });
+    });
+
     let pkg_name_for_bookmark = package.name.clone();
-    let bookmarks_clone = bookmarks.clone();
+    let bookmarks_clone = ctx.bookmarks.clone();
+    let edit_button_clone = edit_button.clone();
+    let bookmark_label_clone = bookmark_label.clone();
     bookmark_check.connect_toggled(move |check| {
         let mut bookmarks = bookmarks_clone.borrow_mut();
         if check.is_active() {
-            if !bookmarks.contains(&pkg_name_for_bookmark) {
-                bookmarks.push(pkg_name_for_bookmark.clone());
-            }
+            bookmarks.add(&pkg_name_for_bookmark);
         } else {
-            bookmarks.retain(|x| x != &pkg_name_for_bookmark);
+            bookmarks.remove(&pkg_name_for_bookmark);
+        }
+        if let Err(e) = bookmarks.save() {
+            eprintln!("Failed to save bookmarks: {}", e);
         }
+        edit_button_clone.set_visible(check.is_active());
+        update_bookmark_label(&bookmark_label_clone, bookmarks.get(&pkg_name_for_bookmark));
     });
-    
-    row_box.append(&bookmark_check);
 
     // Package info
     let info_box = GtkBox::new(Orientation::Vertical, 5);
     info_box.set_hexpand(true);
 
-    let name_label = Label::new(Some(&format!(
-        "{} {} {}",
-        package.name,
-        package.version,
-        if is_installed { "✓ Installed" } else { "" }
-    )));
+    let name_label = Label::new(None);
     name_label.set_halign(gtk4::Align::Start);
     name_label.add_css_class("title-3");
-    if is_installed {
-        name_label.add_css_class("success");
-    }
 
     let desc_label = Label::new(Some(
         &package.description.clone().unwrap_or_else(|| "No description".to_string())
@@ -103,50 +169,421 @@ fn create_package_row(
     info_box.append(&name_label);
     info_box.append(&desc_label);
     info_box.append(&meta_label);
+    info_box.append(&bookmark_label);
 
     row_box.append(&info_box);
 
     // Button box
     let button_box = GtkBox::new(Orientation::Horizontal, 5);
+    button_box.append(&edit_button);
 
     // Details button
     let details_button = Button::with_label("Details");
     let pkg_for_details = package.clone();
-    details_button.connect_clicked(move |btn| {
-        show_package_details(btn, &pkg_for_details);
+    let ctx_for_details = ctx.clone();
+    details_button.connect_clicked(move |_| {
+        show_package_details(&ctx_for_details, &pkg_for_details);
     });
     button_box.append(&details_button);
 
-    // Install button
-    let install_button = Button::with_label(if is_installed { "Reinstall" } else { "Install" });
-    if !is_installed {
-        install_button.add_css_class("suggested-action");
+    // Install and remove buttons
+    let install_widgets = InstallWidgets {
+        name_label: name_label.clone(),
+        install_button: Button::with_label("Install"),
+        remove_button: Button::with_label("Remove"),
+        status_label: Label::new(None),
+    };
+    install_widgets.status_label.set_visible(false);
+    install_widgets.update(&package, ctx);
+
+    // The handlers hold the row's widgets weakly; a strong reference to the
+    // button that owns the handler would keep the row alive forever
+    let ctx_clone = ctx.clone();
+    let widgets = install_widgets.downgrade();
+    let pkg = package.clone();
+    install_widgets.install_button.connect_clicked(move |_| {
+        let Some(widgets) = widgets.upgrade() else {
+            return;
+        };
+        // Reinstalls and updates must not be skipped as "up to date"
+        let action = if ctx_clone.installed.borrow().contains_key(&pkg.name) {
+            HelperAction::Update
+        } else {
+            HelperAction::Install
+        };
+        run_helper_action(&ctx_clone, &widgets, &pkg, action);
+    });
+
+    let ctx_clone = ctx.clone();
+    let widgets = install_widgets.downgrade();
+    let pkg = package.clone();
+    install_widgets.remove_button.connect_clicked(move |_| {
+        if let Some(widgets) = widgets.upgrade() {
+            run_helper_action(&ctx_clone, &widgets, &pkg, HelperAction::Remove);
+        }
+    });
+
+    // Keep the installed marker current as pacman changes the system
+    ctx.row_refreshers.borrow_mut().push(RowRefresher {
+        package,
+        widgets: install_widgets.downgrade(),
+    });
+    button_box.append(&install_widgets.status_label);
+    button_box.append(&install_widgets.remove_button);
+    button_box.append(&install_widgets.install_button);
+
+    row_box.append(&button_box);
+
+    row_box
+}
+
+/// The parts of a package row that show its installed state.
+#[derive(Clone)]
+struct InstallWidgets {
+    name_label: Label,
+    install_button: Button,
+    remove_button: Button,
+    /// Progress and outcome of the last install or removal from this row.
+    status_label: Label,
+}
+
+/// Weak references to a row's `InstallWidgets`, for closures that must not
+/// keep the row alive.
+struct WeakInstallWidgets {
+    name_label: glib::WeakRef<Label>,
+    install_button: glib::WeakRef<Button>,
+    remove_button: glib::WeakRef<Button>,
+    status_label: glib::WeakRef<Label>,
+}
+
+impl WeakInstallWidgets {
+    /// `None` once the row has been freed.
+    fn upgrade(&self) -> Option<InstallWidgets> {
+        Some(InstallWidgets {
+            name_label: self.name_label.upgrade()?,
+            install_button: self.install_button.upgrade()?,
+            remove_button: self.remove_button.upgrade()?,
+            status_label: self.status_label.upgrade()?,
+        })
     }
+}
 
-    let pkg_name = package.name.clone();
-    install_button.connect_clicked(move |btn| {
-        btn.set_label("Opening terminal...");
-        btn.set_sensitive(false);
-        
-        if let Err(e) = crate::installer::spawn_yay_install(&pkg_name) {
-            eprintln!("Failed to start installation: {}", e);
+impl InstallWidgets {
+    fn downgrade(&self) -> WeakInstallWidgets {
+        WeakInstallWidgets {
+            name_label: self.name_label.downgrade(),
+            install_button: self.install_button.downgrade(),
+            remove_button: self.remove_button.downgrade(),
+            status_label: self.status_label.downgrade(),
         }
-        
-        let btn_clone = btn.clone();
-        glib::timeout_add_seconds_local(2, move || {
-            btn_clone.set_label("Install");
-            btn_clone.set_sensitive(true);
-            glib::ControlFlow::Break
+    }
+
+    /// Sets the name line and install button from what is installed: Install,
+    /// Update when the AUR has a newer version, Reinstall otherwise. A package
+    /// whose name another installed package provides or replaces counts as
+    /// satisfied, and so does one that itself provides an installed package.
+    fn update(&self, package: &AurPackage, ctx: &ListContext) {
+        let installed = ctx.installed.borrow();
+        let installed_version = installed.get(&package.name).map(|p| &p.version);
+        let aur_version = Version::parse(&package.version);
+        let update_available = installed_version.is_some_and(|installed| aur_version > *installed);
+        let provider = match installed_version {
+            Some(_) => None,
+            None => crate::package_checker::find_provider(&installed, &ctx.providers.borrow(), &package.name),
+        };
+        // Search results carry no provides; full records do
+        let provided = match installed_version.or(provider.map(|p| &p.version)) {
+            Some(_) => None,
+            None => package
+                .provides
+                .iter()
+                .chain(package.replaces.iter())
+                .flatten()
+                .filter_map(|entry| installed.get(&Dependency::parse(entry).name))
+                .next(),
+        };
+
+        let install_state = match (installed_version, provider, provided) {
+            (Some(installed), _, _) if update_available => format!("⇡ Update from {}", installed),
+            (Some(installed), _, _) if *installed == aur_version => "✓ Installed".to_string(),
+            // Usually a VCS package built more recently than the AUR snapshot
+            (Some(installed), _, _) => format!("✓ Installed {}", installed),
+            (None, Some(provider), _) => format!("✓ Satisfied by {} {}", provider.name, provider.version),
+            (None, None, Some(other)) => format!("Alternative to installed {} {}", other.name, other.version),
+            (None, None, None) => String::new(),
+        };
+        self.name_label.set_text(&format!("{} {} {}", package.name, package.version, install_state));
+        self.name_label.remove_css_class("accent");
+        self.name_label.remove_css_class("success");
+        if update_available {
+            self.name_label.add_css_class("accent");
+        } else if installed_version.is_some() || provider.is_some() {
+            self.name_label.add_css_class("success");
+        }
+
+        self.install_button.set_label(if update_available {
+            "Update"
+        } else if installed_version.is_some() {
+            "Reinstall"
+        } else {
+            "Install"
         });
+        // Installing over a provider would usually conflict with it
+        let suggested = update_available || (installed_version.is_none() && provider.is_none() && provided.is_none());
+        if suggested {
+            self.install_button.add_css_class("suggested-action");
+        } else {
+            self.install_button.remove_css_class("suggested-action");
+        }
+        self.remove_button.set_visible(installed_version.is_some());
+    }
+
+    fn set_sensitive(&self, sensitive: bool) {
+        self.install_button.set_sensitive(sensitive);
+        self.remove_button.set_sensitive(sensitive);
+    }
+
+    /// Shows `text` next to the buttons, dimmed unless `css_class` says
+    /// otherwise.
+    fn set_status(&self, text: &str, css_class: Option<&str>, tooltip: Option<&str>) {
+        for class in ["dim-label", "success", "error"] {
+            self.status_label.remove_css_class(class);
+        }
+        self.status_label.add_css_class(css_class.unwrap_or("dim-label"));
+        self.status_label.set_text(text);
+        self.status_label.set_tooltip_text(tooltip);
+        self.status_label.set_visible(true);
+    }
+}
+
+/// Runs `action` on the row's package, showing on the row that it is in
+/// progress and then whether it succeeded.
+fn run_helper_action(ctx: &ListContext, widgets: &InstallWidgets, package: &AurPackage, action: HelperAction) {
+    widgets.set_sensitive(false);
+    widgets.set_status(action_progress(action), None, None);
+
+    let widgets = widgets.clone();
+    let package = package.clone();
+    let ctx_clone = ctx.clone();
+    run_action(ctx, action, vec![package.name.clone()], move |outcome| {
+        if outcome.success() {
+            widgets.set_status(&format!("✓ {}", action_done(action)), Some("success"), None);
+        } else {
+            eprintln!("{} of {}: {}", action_title(action), package.name, outcome);
+            widgets.set_status("✗ Failed", Some("error"), Some(&outcome.to_string()));
+        }
+        widgets.update(&package, &ctx_clone);
+        widgets.set_sensitive(true);
     });
-    button_box.append(&install_button);
+}
 
-    row_box.append(&button_box);
+/// Runs `action` on `package_names` in the built-in terminal, or in an
+/// external one if the user prefers. Once the command ends the installed
+/// packages are re-read and a desktop notification is sent, then `on_exit`
+/// gets the outcome.
+pub fn run_action(
+    ctx: &ListContext,
+    action: HelperAction,
+    package_names: Vec<String>,
+    on_exit: impl FnOnce(CommandOutcome) + 'static,
+) {
+    // Copied, since a native build resolves its dependencies across awaits
+    let settings = ctx.settings.borrow().clone();
+    let installed = ctx.installed.borrow().clone();
+    let ctx = ctx.clone();
+    glib::MainContext::default().spawn_local(async move {
+        let name_refs: Vec<&str> = package_names.iter().map(String::as_str).collect();
+        let command =
+            crate::installer::action_command(&settings, &ctx.client, &installed, action, &name_refs).await;
+        let command = match command {
+            Ok(command) => command,
+            Err(e) => return on_exit(CommandOutcome::NotStarted(e.to_string())),
+        };
+
+        let title = format!("{}: {}", action_title(action), package_names.join(" "));
+        let finished = move |outcome| finish_action(ctx, action, package_names, outcome, on_exit);
+        if settings.external_terminal {
+            match crate::installer::spawn_in_terminal(&command) {
+                Ok(status_file) => watch_exit_status(status_file, finished),
+                Err(e) => finished(CommandOutcome::NotStarted(e.to_string())),
+            }
+        } else {
+            super::terminal_view::run_in_terminal(&title, &command, finished);
+        }
+    });
+}
+
+/// Polls the status file of a command running in an external terminal
+/// until it has ended.
+fn watch_exit_status(status_file: PathBuf, on_exit: impl FnOnce(CommandOutcome) + 'static) {
+    let mut on_exit = Some(on_exit);
+    glib::timeout_add_local(EXIT_STATUS_POLL, move || {
+        match crate::installer::read_exit_status(&status_file) {
+            Some(outcome) => {
+                if let Some(on_exit) = on_exit.take() {
+                    on_exit(outcome);
+                }
+                glib::ControlFlow::Break
+            }
+            None => glib::ControlFlow::Continue,
+        }
+    });
+}
+
+fn finish_action(
+    ctx: ListContext,
+    action: HelperAction,
+    package_names: Vec<String>,
+    outcome: CommandOutcome,
+    on_exit: impl FnOnce(CommandOutcome) + 'static,
+) {
+    glib::MainContext::default().spawn_local(async move {
+        if !matches!(outcome, CommandOutcome::NotStarted(_)) {
+            let installed = crate::package_checker::load_installed_packages().await;
+            ctx.set_installed(installed);
+            refresh_installed_state(&ctx);
+            notify_outcome(action, &package_names, &outcome);
+        }
+        on_exit(outcome);
+    });
+}
+
+/// A desktop notification, since builds can take long enough for the user
+/// to have moved on.
+fn notify_outcome(action: HelperAction, package_names: &[String], outcome: &CommandOutcome) {
+    let Some(app) = gio::Application::default() else {
+        return;
+    };
+    let names = package_names.join(", ");
+    let notification = if outcome.success() {
+        gio::Notification::new(&format!("{} {}", action_done(action), names))
+    } else {
+        let notification = gio::Notification::new(&format!("{} failed: {}", action_title(action), names));
+        notification.set_body(Some(&outcome.to_string()));
+        notification
+    };
+    app.send_notification(None, &notification);
+}
+
+fn action_title(action: HelperAction) -> &'static str {
+    match action {
+        HelperAction::Install => "Install",
+        HelperAction::Update => "Update",
+        HelperAction::Remove => "Remove",
+    }
+}
+
+fn action_progress(action: HelperAction) -> &'static str {
+    match action {
+        HelperAction::Install => "Installing...",
+        HelperAction::Update => "Updating...",
+        HelperAction::Remove => "Removing...",
+    }
+}
+
+fn action_done(action: HelperAction) -> &'static str {
+    match action {
+        HelperAction::Install => "Installed",
+        HelperAction::Update => "Updated",
+        HelperAction::Remove => "Removed",
+    }
+}
+
+/// Re-applies the installed state to every live package row, after
+/// `ctx.installed` has been replaced.
+pub fn refresh_installed_state(ctx: &ListContext) {
+    ctx.row_refreshers.borrow_mut().retain(|row| {
+        let Some(widgets) = row.widgets.upgrade() else {
+            return false;
+        };
+        // Rows busy with an action update when it finishes
+        if widgets.install_button.is_sensitive() {
+            widgets.update(&row.package, ctx);
+        }
+        true
+    });
+}
+
+/// Forgets rows that have been freed, e.g. after a list was cleared.
+pub fn prune_row_refreshers(ctx: &ListContext) {
+    ctx.row_refreshers.borrow_mut().retain(|row| row.widgets.upgrade().is_some());
+}
+
+/// Shows a bookmark's collections, tags and note, hiding the label when
+/// there is nothing to show.
+fn update_bookmark_label(label: &Label, bookmark: Option<&Bookmark>) {
+    let mut parts = Vec::new();
+    if let Some(bookmark) = bookmark {
+        if !bookmark.collections.is_empty() {
+            parts.push(format!("In: {}", bookmark.collections.join(", ")));
+        }
+        if !bookmark.tags.is_empty() {
+            let tags: Vec<String> = bookmark.tags.iter().map(|tag| format!("#{}", tag)).collect();
+            parts.push(tags.join(" "));
+        }
+        if !bookmark.note.is_empty() {
+            parts.push(format!("Note: {}", bookmark.note));
+        }
+    }
+    label.set_text(&parts.join(" | "));
+    label.set_visible(!parts.is_empty());
+}
+
+/// Row for a bookmarked package the AUR no longer knows about. The only
+/// useful action left is dropping the bookmark; `on_removed` runs after.
+pub fn create_missing_row(name: &str, ctx: &ListContext, on_removed: impl Fn() + 'static) -> GtkBox {
+    let row_box = GtkBox::new(Orientation::Horizontal, 10);
+    row_box.set_margin_top(5);
+    row_box.set_margin_bottom(5);
+    row_box.set_margin_start(5);
+    row_box.set_margin_end(5);
+
+    let info_box = GtkBox::new(Orientation::Vertical, 5);
+    info_box.set_hexpand(true);
+
+    let name_label = Label::new(Some(name));
+    name_label.set_halign(gtk4::Align::Start);
+    name_label.add_css_class("title-3");
+    name_label.add_css_class("dim-label");
+
+    let gone_label = Label::new(Some("Gone: this package is no longer in the AUR"));
+    gone_label.set_halign(gtk4::Align::Start);
+    gone_label.add_css_class("warning");
+
+    info_box.append(&name_label);
+    info_box.append(&gone_label);
+    row_box.append(&info_box);
+
+    let remove_button = Button::with_label("Remove Bookmark");
+    remove_button.set_valign(gtk4::Align::Center);
+    let name = name.to_string();
+    let bookmarks = ctx.bookmarks.clone();
+    remove_button.connect_clicked(move |_| {
+        {
+            let mut bookmarks = bookmarks.borrow_mut();
+            bookmarks.remove(&name);
+            if let Err(e) = bookmarks.save() {
+                eprintln!("Failed to save bookmarks: {}", e);
+            }
+        }
+        on_removed();
+    });
+    row_box.append(&remove_button);
 
     row_box
 }
 
-fn show_package_details(parent_widget: &Button, package: &AurPackage) {
+/// Opens the details dialog for a package known only by name; everything
+/// else is fetched by the dialog itself.
+pub fn show_package_details_by_name(ctx: &ListContext, name: &str) {
+    let package = AurPackage {
+        name: name.to_string(),
+        ..Default::default()
+    };
+    show_package_details(ctx, &package);
+}
+
+fn show_package_details(ctx: &ListContext, package: &AurPackage) {
     let dialog = gtk4::Window::builder()
         .title(format!("Package Details: {}", package.name))
         .modal(true)
@@ -166,32 +603,140 @@ fn show_package_details(parent_widget: &Button, package: &AurPackage) {
     header_label.set_halign(gtk4::Align::Start);
     main_box.append(&header_label);
 
-    if let Some(desc) = &package.description {
-        let desc_label = Label::new(Some(desc));
-        desc_label.set_wrap(true);
-        desc_label.set_xalign(0.0);
-        desc_label.set_margin_bottom(10);
-        main_box.append(&desc_label);
-    }
+    let desc_label = Label::new(package.description.as_deref());
+    desc_label.set_wrap(true);
+    desc_label.set_xalign(0.0);
+    desc_label.set_margin_bottom(10);
+    desc_label.set_visible(package.description.is_some());
+    main_box.append(&desc_label);
 
     // Scrolled area for details
     let scrolled = gtk4::ScrolledWindow::builder()
         .vexpand(true)
         .build();
 
+    // Search results only carry part of the metadata, so fetch the full
+    // record (once per session) before rendering dependencies.
+    let cached = ctx.details_cache.borrow().get(&package.name).cloned();
+    match cached {
+        Some(full) => {
+            scrolled.set_child(Some(&build_details_box(&full, ctx)));
+        }
+        None => {
+            let loading_box = GtkBox::new(Orientation::Vertical, 10);
+            loading_box.set_valign(gtk4::Align::Center);
+            loading_box.set_halign(gtk4::Align::Center);
+            let spinner = gtk4::Spinner::new();
+            spinner.set_size_request(32, 32);
+            spinner.start();
+            let loading_label = Label::new(Some("Loading package details..."));
+            loading_label.add_css_class("dim-label");
+            loading_box.append(&spinner);
+            loading_box.append(&loading_label);
+            scrolled.set_child(Some(&loading_box));
+
+            let ctx = ctx.clone();
+            let partial = package.clone();
+            let scrolled = scrolled.clone();
+            glib::MainContext::default().spawn_local(async move {
+                match ctx.client.info(&partial.name).await {
+                    Ok(full) => {
+                        ctx.details_cache.borrow_mut().insert(full.name.clone(), full.clone());
+                        header_label.set_text(&format!("{} {}", full.name, full.version));
+                        desc_label.set_text(full.description.as_deref().unwrap_or(""));
+                        desc_label.set_visible(full.description.is_some());
+                        scrolled.set_child(Some(&build_details_box(&full, &ctx)));
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to load details for {}: {}", partial.name, e);
+                        let details_box = build_details_box(&partial, &ctx);
+                        let warning = Label::new(Some(&format!(
+                            "Could not load full details ({}). Dependencies may be incomplete.",
+                            e
+                        )));
+                        warning.add_css_class("warning");
+                        warning.set_wrap(true);
+                        warning.set_xalign(0.0);
+                        details_box.prepend(&warning);
+                        scrolled.set_child(Some(&details_box));
+                    }
+                }
+            });
+        }
+    }
+
+    main_box.append(&scrolled);
+
+    // Close button
+    let close_btn = Button::with_label("Close");
+    close_btn.add_css_class("suggested-action");
+    close_btn.set_halign(gtk4::Align::End);
+    let dialog_clone = dialog.clone();
+    close_btn.connect_clicked(move |_| {
+        dialog_clone.close();
+    });
+    main_box.append(&close_btn);
+
+    dialog.set_child(Some(&main_box));
+    dialog.present();
+}
+
+fn build_details_box(package: &AurPackage, ctx: &ListContext) -> GtkBox {
     let details_box = GtkBox::new(Orientation::Vertical, 10);
 
     // AUR Page Link
-    let aur_link = format!("https://aur.archlinux.org/packages/{}", package.name);
+    let aur_link = format!("{}/packages/{}", ctx.client.base_url(), package.name);
     let link_button = gtk4::LinkButton::with_label(&aur_link, "View on AUR");
     link_button.set_halign(gtk4::Align::Start);
     details_box.append(&link_button);
 
+    // Installed version
+    let installed = ctx.installed.borrow();
+    if let Some(local) = installed.get(&package.name) {
+        let reason = match local.reason {
+            InstallReason::Explicit => "explicitly installed",
+            InstallReason::Dependency => "installed as a dependency",
+        };
+        add_detail_row(&details_box, "Installed:", &format!("{} ({})", local.version, reason));
+        if let Some(date) = local.install_date {
+            add_detail_row(&details_box, "Installed On:", &format_timestamp(date));
+        }
+        if let Some(packager) = &local.packager {
+            add_detail_row(&details_box, "Packager:", packager);
+        }
+    } else if let Some(provider) =
+        crate::package_checker::find_provider(&installed, &ctx.providers.borrow(), &package.name)
+    {
+        add_detail_row(&details_box, "Satisfied By:", &format!("{} {}", provider.name, provider.version));
+    }
+
+    // Package base (differs from the name for split packages)
+    if let Some(base) = &package.package_base {
+        add_detail_row(&details_box, "Package Base:", base);
+    }
+
+    // Upstream URL
+    if let Some(url) = &package.url {
+        add_detail_row(&details_box, "Upstream URL:", url);
+    }
+
     // Maintainer
     if let Some(maintainer) = &package.maintainer {
         add_detail_row(&details_box, "Maintainer:", maintainer);
     }
 
+    // Co-maintainers
+    if let Some(comaintainers) = &package.comaintainers {
+        if !comaintainers.is_empty() {
+            add_detail_row(&details_box, "Co-maintainers:", &comaintainers.join(", "));
+        }
+    }
+
+    // Submitter
+    if let Some(submitter) = &package.submitter {
+        add_detail_row(&details_box, "Submitter:", submitter);
+    }
+
     // License
     if let Some(licenses) = &package.license {
         add_detail_row(&details_box, "License:", &licenses.join(", "));
@@ -201,24 +746,61 @@ fn show_package_details(parent_widget: &Button, package: &AurPackage) {
     add_detail_row(&details_box, "Votes:", &package.votes.unwrap_or(0).to_string());
     add_detail_row(&details_box, "Popularity:", &format!("{:.2}", package.popularity.unwrap_or(0.0)));
 
+    // Keywords and groups
+    if let Some(keywords) = &package.keywords {
+        if !keywords.is_empty() {
+            add_detail_row(&details_box, "Keywords:", &keywords.join(", "));
+        }
+    }
+    if let Some(groups) = &package.groups {
+        if !groups.is_empty() {
+            add_detail_row(&details_box, "Groups:", &groups.join(", "));
+        }
+    }
+
+    // Dates
+    if let Some(submitted) = package.first_submitted {
+        add_detail_row(&details_box, "First Submitted:", &format_timestamp(submitted));
+    }
+    if let Some(modified) = package.last_modified {
+        add_detail_row(&details_box, "Last Updated:", &format_timestamp(modified));
+    }
+    if let Some(flagged) = package.out_of_date {
+        add_detail_row(&details_box, "Flagged Out-of-date:", &format_timestamp(flagged));
+    }
+
     // Dependencies
     if let Some(deps) = &package.depends {
         if !deps.is_empty() {
-            add_detail_section(&details_box, "Dependencies:", deps);
+            add_detail_section(&details_box, "Dependencies:", &describe_dependencies(deps, &installed));
         }
     }
 
     // Make Dependencies
     if let Some(makedeps) = &package.makedepends {
         if !makedeps.is_empty() {
-            add_detail_section(&details_box, "Make Dependencies:", makedeps);
+            add_detail_section(&details_box, "Make Dependencies:", &describe_dependencies(makedeps, &installed));
         }
     }
 
     // Optional Dependencies
     if let Some(optdeps) = &package.optdepends {
         if !optdeps.is_empty() {
-            add_detail_section(&details_box, "Optional Dependencies:", optdeps);
+            add_detail_section(&details_box, "Optional Dependencies:", &describe_dependencies(optdeps, &installed));
+        }
+    }
+
+    // Check Dependencies
+    if let Some(checkdeps) = &package.checkdepends {
+        if !checkdeps.is_empty() {
+            add_detail_section(&details_box, "Check Dependencies:", &describe_dependencies(checkdeps, &installed));
+        }
+    }
+
+    // Provides
+    if let Some(provides) = &package.provides {
+        if !provides.is_empty() {
+            add_detail_section(&details_box, "Provides:", provides);
         }
     }
 
@@ -229,21 +811,34 @@ fn show_package_details(parent_widget: &Button, package: &AurPackage) {
         }
     }
 
-    scrolled.set_child(Some(&details_box));
-    main_box.append(&scrolled);
+    // Replaces
+    if let Some(replaces) = &package.replaces {
+        if !replaces.is_empty() {
+            add_detail_section(&details_box, "Replaces:", replaces);
+        }
+    }
 
-    // Close button
-    let close_btn = Button::with_label("Close");
-    close_btn.add_css_class("suggested-action");
-    close_btn.set_halign(gtk4::Align::End);
-    let dialog_clone = dialog.clone();
-    close_btn.connect_clicked(move |_| {
-        dialog_clone.close();
-    });
-    main_box.append(&close_btn);
+    details_box
+}
 
-    dialog.set_child(Some(&main_box));
-    dialog.present();
+/// Marks each dependency with the installed package that satisfies it,
+/// directly or through its provides, or with the installed version that
+/// does not.
+fn describe_dependencies(entries: &[String], installed: &HashMap<String, InstalledPackage>) -> Vec<String> {
+    entries
+        .iter()
+        .map(|entry| {
+            let dependency = Dependency::parse(entry);
+            match crate::package_checker::find_satisfier(installed, &dependency) {
+                Some(local) if local.name == dependency.name => format!("{}  ✓ {}", entry, local.version),
+                Some(local) => format!("{}  ✓ via {} {}", entry, local.name, local.version),
+                None => match installed.get(&dependency.name) {
+                    Some(local) => format!("{}  ✗ {} installed", entry, local.version),
+                    None => entry.clone(),
+                },
+            }
+        })
+        .collect()
 }
 
 fn add_detail_row(container: &GtkBox, label: &str, value: &str) {
@@ -280,3 +875,10 @@ fn add_detail_section(container: &GtkBox, title: &str, items: &[String]) {
         container.append(&item_label);
     }
 }
+
+fn format_timestamp(timestamp: Timestamp) -> String {
+    glib::DateTime::from_unix_local(timestamp.as_unix())
+        .and_then(|dt| dt.format("%Y-%m-%d %H:%M"))
+        .map(|s| s.to_string())
+        .unwrap_or_else(|_| timestamp.as_unix().to_string())
+}
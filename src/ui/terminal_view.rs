@@ -0,0 +1,101 @@
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, Label, Orientation};
+use vte4::prelude::*;
+use crate::installer::CommandOutcome;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+const SCROLLBACK_LINES: i64 = 10_000;
+
+/// Runs `command` through `sh` in a pty shown in its own window. The window
+/// stays open after the command ends so its output can still be scrolled
+/// and copied; `on_exit` gets the outcome.
+pub fn run_in_terminal(title: &str, command: &str, on_exit: impl FnOnce(CommandOutcome) + 'static) {
+    let window = gtk4::Window::builder()
+        .title(title)
+        .default_width(800)
+        .default_height(500)
+        .build();
+
+    let main_box = GtkBox::new(Orientation::Vertical, 0);
+
+    let terminal = vte4::Terminal::new();
+    terminal.set_scrollback_lines(SCROLLBACK_LINES);
+    terminal.set_hexpand(true);
+    terminal.set_vexpand(true);
+    let scrolled = gtk4::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .vscrollbar_policy(gtk4::PolicyType::Automatic)
+        .child(&terminal)
+        .build();
+    main_box.append(&scrolled);
+
+    // Status bar
+    let status_box = GtkBox::new(Orientation::Horizontal, 10);
+    status_box.set_margin_top(10);
+    status_box.set_margin_bottom(10);
+    status_box.set_margin_start(10);
+    status_box.set_margin_end(10);
+    let status_label = Label::new(Some("Running..."));
+    status_label.set_hexpand(true);
+    status_label.set_halign(gtk4::Align::Start);
+    let close_btn = Button::with_label("Close");
+    close_btn.set_sensitive(false);
+    status_box.append(&status_label);
+    status_box.append(&close_btn);
+    main_box.append(&status_box);
+
+    let window_clone = window.clone();
+    close_btn.connect_clicked(move |_| {
+        window_clone.close();
+    });
+
+    // Closing would hang up on the command, so wait for it to end; Ctrl+C in
+    // the terminal stops it
+    let running = Rc::new(Cell::new(true));
+    let running_clone = running.clone();
+    window.connect_close_request(move |_| {
+        if running_clone.get() {
+            glib::Propagation::Stop
+        } else {
+            glib::Propagation::Proceed
+        }
+    });
+
+    // Either the spawn fails or the child exits, never both
+    let on_exit = RefCell::new(Some(on_exit));
+    let report = Rc::new(move |outcome: CommandOutcome| {
+        running.set(false);
+        status_label.set_text(&outcome.to_string());
+        status_label.add_css_class(if outcome.success() { "success" } else { "error" });
+        close_btn.set_sensitive(true);
+        if let Some(on_exit) = on_exit.borrow_mut().take() {
+            on_exit(outcome);
+        }
+    });
+
+    let report_clone = report.clone();
+    terminal.connect_child_exited(move |_, status| {
+        report_clone(CommandOutcome::from_wait_status(status));
+    });
+
+    terminal.spawn_async(
+        vte4::PtyFlags::DEFAULT,
+        None,
+        &["/bin/sh", "-c", command],
+        &[],
+        glib::SpawnFlags::DEFAULT,
+        || {},
+        -1,
+        None::<&gio::Cancellable>,
+        move |result| {
+            if let Err(e) = result {
+                report(CommandOutcome::NotStarted(e.to_string()));
+            }
+        },
+    );
+
+    window.set_child(Some(&main_box));
+    window.present();
+    terminal.grab_focus();
+}
@@ -0,0 +1,185 @@
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, Label, ListBox, Orientation};
+use crate::aur_client::AurError;
+use crate::aur_helper::HelperAction;
+use crate::updates::Update;
+use crate::version::Version;
+use super::package_list::ListContext;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// The updates page: installed foreign packages whose AUR version is newer
+/// than the installed one.
+#[derive(Clone)]
+pub struct UpdatesView {
+    pub widget: GtkBox,
+    summary_label: Label,
+    update_all_button: Button,
+    list: ListBox,
+    ctx: ListContext,
+    updates: Rc<RefCell<Vec<Update>>>,
+}
+
+impl UpdatesView {
+    pub fn new(ctx: &ListContext) -> Self {
+        let widget = GtkBox::new(Orientation::Vertical, 0);
+
+        let controls = GtkBox::new(Orientation::Horizontal, 10);
+        controls.set_margin_top(10);
+        controls.set_margin_start(15);
+        controls.set_margin_end(10);
+        let title_box = GtkBox::new(Orientation::Vertical, 2);
+        title_box.set_hexpand(true);
+        let title = Label::new(Some("Updates"));
+        title.add_css_class("title-2");
+        title.set_halign(gtk4::Align::Start);
+        let summary_label = Label::new(None);
+        summary_label.add_css_class("dim-label");
+        summary_label.set_halign(gtk4::Align::Start);
+        title_box.append(&title);
+        title_box.append(&summary_label);
+        controls.append(&title_box);
+        let update_all_button = Button::with_label("Update All");
+        update_all_button.add_css_class("suggested-action");
+        update_all_button.set_valign(gtk4::Align::Center);
+        controls.append(&update_all_button);
+        widget.append(&controls);
+
+        let scrolled = gtk4::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vexpand(true)
+            .build();
+        let list = super::package_list::create_package_list();
+        scrolled.set_child(Some(&list));
+        widget.append(&scrolled);
+
+        let view = UpdatesView {
+            widget,
+            summary_label,
+            update_all_button,
+            list,
+            ctx: ctx.clone(),
+            updates: Rc::new(RefCell::new(Vec::new())),
+        };
+
+        let view_clone = view.clone();
+        view.update_all_button.connect_clicked(move |_| {
+            let names = view_clone.updates.borrow().iter().map(|u| u.package.name.clone()).collect();
+            view_clone.start_update(names);
+        });
+
+        view
+    }
+
+    /// Reads the installed foreign packages, compares them with the AUR and
+    /// redraws the page.
+    pub async fn load(&self) -> Result<(), AurError> {
+        let installed = crate::package_checker::load_installed_packages().await;
+        let foreign: HashMap<String, Version> = installed
+            .values()
+            .filter(|p| p.foreign)
+            .map(|p| (p.name.clone(), p.version.clone()))
+            .collect();
+        self.ctx.set_installed(installed);
+        super::package_list::refresh_installed_state(&self.ctx);
+        let foreign_count = foreign.len();
+        let updates = crate::updates::find_updates(&self.ctx.client, foreign).await?;
+
+        self.summary_label.set_text(&match updates.len() {
+            0 => format!("All {} AUR packages are up to date", foreign_count),
+            1 => format!("1 of {} AUR packages can be updated", foreign_count),
+            n => format!("{} of {} AUR packages can be updated", n, foreign_count),
+        });
+        self.update_all_button.set_sensitive(!updates.is_empty());
+        *self.updates.borrow_mut() = updates;
+        self.render();
+        Ok(())
+    }
+
+    fn render(&self) {
+        while let Some(child) = self.list.first_child() {
+            self.list.remove(&child);
+        }
+
+        for update in self.updates.borrow().iter() {
+            self.list.append(&self.create_update_row(update));
+        }
+    }
+
+    /// Updates `names`, then reloads the page so finished updates drop off.
+    fn start_update(&self, names: Vec<String>) {
+        if names.is_empty() {
+            return;
+        }
+        self.update_all_button.set_sensitive(false);
+        let view = self.clone();
+        super::package_list::run_action(&self.ctx, HelperAction::Update, names, move |outcome| {
+            if !outcome.success() {
+                eprintln!("Update failed: {}", outcome);
+            }
+            glib::MainContext::default().spawn_local(async move {
+                if let Err(e) = view.load().await {
+                    eprintln!("Failed to check for updates: {}", e);
+                    view.update_all_button.set_sensitive(true);
+                }
+            });
+        });
+    }
+
+    fn create_update_row(&self, update: &Update) -> GtkBox {
+        let row_box = GtkBox::new(Orientation::Horizontal, 10);
+        row_box.set_margin_top(5);
+        row_box.set_margin_bottom(5);
+        row_box.set_margin_start(5);
+        row_box.set_margin_end(5);
+
+        let info_box = GtkBox::new(Orientation::Vertical, 5);
+        info_box.set_hexpand(true);
+
+        let name_label = Label::new(Some(&update.package.name));
+        name_label.set_halign(gtk4::Align::Start);
+        name_label.add_css_class("title-3");
+
+        let version_label = Label::new(Some(&format!(
+            "{} → {}",
+            update.installed_version, update.package.version
+        )));
+        version_label.set_halign(gtk4::Align::Start);
+        version_label.add_css_class("accent");
+
+        info_box.append(&name_label);
+        info_box.append(&version_label);
+        if update.package.out_of_date.is_some() {
+            let flagged_label = Label::new(Some("Flagged out-of-date in the AUR"));
+            flagged_label.set_halign(gtk4::Align::Start);
+            flagged_label.add_css_class("warning");
+            info_box.append(&flagged_label);
+        }
+        row_box.append(&info_box);
+
+        // Button box
+        let button_box = GtkBox::new(Orientation::Horizontal, 5);
+        button_box.set_valign(gtk4::Align::Center);
+
+        let details_button = Button::with_label("Details");
+        let ctx = self.ctx.clone();
+        let name = update.package.name.clone();
+        details_button.connect_clicked(move |_| {
+            super::package_list::show_package_details_by_name(&ctx, &name);
+        });
+        button_box.append(&details_button);
+
+        let update_button = Button::with_label("Update");
+        let view = self.clone();
+        let name = update.package.name.clone();
+        update_button.connect_clicked(move |_| {
+            view.start_update(vec![name.clone()]);
+        });
+        button_box.append(&update_button);
+
+        row_box.append(&button_box);
+        row_box
+    }
+}
@@ -1,8 +1,31 @@
 use gtk4::prelude::*;
 use gtk4::{Application, ApplicationWindow, Box as GtkBox, Orientation, ScrolledWindow, DropDown, Spinner, Button};
 use libadwaita as adw;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::rc::Rc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use crate::aur_client::{AurClient, AurError, AurPackage, SearchField};
+use crate::bookmarks::Bookmarks;
+use crate::rss_feed::FeedEntry;
+use crate::settings::Settings;
+use super::bookmarks_view::BookmarksView;
+use super::completion::{Completion, Suggestion};
+use super::package_list::ListContext;
+use super::updates_view::UpdatesView;
+
+/// The RPC rejects shorter search arguments ("Query arg too small").
+const MIN_QUERY_LEN: usize = 2;
+/// Quiet period after the last keystroke before a search is sent.
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Quiet period after the last change to pacman's database before it is
+/// reread.
+const INSTALLED_DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// The AUR regenerates the metadata archive daily.
+const INDEX_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum SortMode {
@@ -12,7 +35,7 @@ pub enum SortMode {
     LastModified,
 }
 
-pub fn build_ui(app: &Application) -> ApplicationWindow {
+pub fn build_ui(app: &Application, client: AurClient) -> ApplicationWindow {
     let window = ApplicationWindow::builder()
         .application(app)
         .title("AUR Browser")
@@ -22,10 +45,36 @@ pub fn build_ui(app: &Application) -> ApplicationWindow {
 
     let main_box = GtkBox::new(Orientation::Vertical, 0);
 
-    // Header bar with favorite/bookmark button
+    // Header bar with home and favorite/bookmark buttons
     let header = adw::HeaderBar::new();
+    let home_btn = Button::with_label("⌂ Home");
+    home_btn.set_tooltip_text(Some("Recently updated and new packages"));
+    header.pack_start(&home_btn);
     let bookmarks_btn = Button::with_label("★ Bookmarks");
     header.pack_start(&bookmarks_btn);
+    let updates_btn = Button::with_label("⇡ Updates");
+    updates_btn.set_tooltip_text(Some("Installed AUR packages with newer versions"));
+    header.pack_start(&updates_btn);
+
+    // Application menu
+    let app_menu = gio::Menu::new();
+    app_menu.append(Some("AUR Helper..."), Some("win.helper-settings"));
+    app_menu.append(Some("Import Manifest..."), Some("win.import-manifest"));
+    app_menu.append(Some("Export Manifest..."), Some("win.export-manifest"));
+    app_menu.append(Some("Clear Cache"), Some("win.clear-cache"));
+    let menu_btn = gtk4::MenuButton::builder()
+        .icon_name("open-menu-symbolic")
+        .menu_model(&app_menu)
+        .build();
+    header.pack_end(&menu_btn);
+
+    // Offline index status and refresh
+    let index_refresh_btn = Button::with_label("⟳");
+    index_refresh_btn.set_tooltip_text(Some("Refresh offline package index"));
+    header.pack_end(&index_refresh_btn);
+    let index_label = gtk4::Label::new(Some("No offline index"));
+    index_label.add_css_class("dim-label");
+    header.pack_end(&index_label);
     main_box.append(&header);
 
     // Search and sort controls
@@ -35,9 +84,19 @@ pub fn build_ui(app: &Application) -> ApplicationWindow {
     controls_box.set_margin_start(10);
     controls_box.set_margin_end(10);
 
+    // Search field selector
+    let field_labels: Vec<&str> = SearchField::ALL.iter().map(|f| f.label()).collect();
+    let field_dropdown = DropDown::new(
+        Some(gtk4::StringList::new(&field_labels)),
+        None::<gtk4::Expression>,
+    );
+    field_dropdown.set_selected(0);
+    field_dropdown.set_tooltip_text(Some("Field to search by"));
+    controls_box.append(&field_dropdown);
+
     // Search bar
     let search_entry = gtk4::Entry::builder()
-        .placeholder_text("Search AUR packages... (Press Enter)")
+        .placeholder_text("Search AUR packages...")
         .hexpand(true)
         .build();
     controls_box.append(&search_entry);
@@ -72,6 +131,31 @@ pub fn build_ui(app: &Application) -> ApplicationWindow {
     scrolled_window.set_child(Some(&package_list_box));
     content_stack.add_named(&scrolled_window, Some("packages"));
 
+    // Start page: recently updated and newly submitted packages
+    let start_scrolled = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .vscrollbar_policy(gtk4::PolicyType::Automatic)
+        .build();
+    let start_box = GtkBox::new(Orientation::Vertical, 0);
+    let updated_label = gtk4::Label::new(Some("Recently Updated"));
+    updated_label.add_css_class("title-2");
+    updated_label.set_halign(gtk4::Align::Start);
+    updated_label.set_margin_start(15);
+    updated_label.set_margin_top(10);
+    let updated_list_box = super::package_list::create_package_list();
+    let new_label = gtk4::Label::new(Some("New Packages"));
+    new_label.add_css_class("title-2");
+    new_label.set_halign(gtk4::Align::Start);
+    new_label.set_margin_start(15);
+    new_label.set_margin_top(10);
+    let new_list_box = super::package_list::create_package_list();
+    start_box.append(&updated_label);
+    start_box.append(&updated_list_box);
+    start_box.append(&new_label);
+    start_box.append(&new_list_box);
+    start_scrolled.set_child(Some(&start_box));
+    content_stack.add_named(&start_scrolled, Some("start"));
+
     // Loading view
     let loading_box = GtkBox::new(Orientation::Vertical, 20);
     loading_box.set_valign(gtk4::Align::Center);
@@ -95,6 +179,9 @@ pub fn build_ui(app: &Application) -> ApplicationWindow {
     error_label.add_css_class("title-2");
     let error_detail = gtk4::Label::new(Some("Check your internet connection"));
     error_detail.add_css_class("dim-label");
+    error_detail.set_wrap(true);
+    error_detail.set_max_width_chars(60);
+    error_detail.set_justify(gtk4::Justification::Center);
     let retry_button = Button::with_label("Retry");
     retry_button.add_css_class("suggested-action");
     retry_button.set_size_request(120, -1);
@@ -119,183 +206,644 @@ pub fn build_ui(app: &Application) -> ApplicationWindow {
     empty_box.append(&empty_detail);
     content_stack.add_named(&empty_box, Some("empty"));
 
-    main_box.append(&content_stack);
+    // Toasts for short confirmations
+    let toast_overlay = adw::ToastOverlay::new();
+    toast_overlay.set_child(Some(&content_stack));
+    main_box.append(&toast_overlay);
     window.set_child(Some(&main_box));
 
     // Store current packages and bookmarks
-    let current_packages: Rc<RefCell<Vec<crate::aur_client::AurPackage>>> = Rc::new(RefCell::new(Vec::new()));
-    let bookmarked_packages: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
-    let installed_packages = Rc::new(crate::package_checker::get_installed_packages());
+    let current_packages: Rc<RefCell<Vec<AurPackage>>> = Rc::new(RefCell::new(Vec::new()));
+    let bookmarks = Rc::new(RefCell::new(Bookmarks::load(&Bookmarks::default_path())));
+    let list_ctx = ListContext {
+        client: client.clone(),
+        installed: Rc::new(RefCell::new(HashMap::new())),
+        providers: Rc::new(RefCell::new(HashMap::new())),
+        bookmarks,
+        settings: Rc::new(RefCell::new(Settings::load(&Settings::default_path()))),
+        details_cache: Rc::new(RefCell::new(HashMap::new())),
+        row_refreshers: Rc::new(RefCell::new(Vec::new())),
+    };
+
+    // Bumped by every load into the content area; see `Search`.
+    let load_generation = Rc::new(Cell::new(0u64));
+    let start_page = StartPage {
+        updated_list: updated_list_box.clone(),
+        new_list: new_list_box.clone(),
+        loaded: Rc::new(Cell::new(false)),
+    };
 
-    // Load initial packages
+    // Read the pacman database off the main thread, then load the start
+    // page so its rows know what is installed
     content_stack.set_visible_child_name("loading");
-    let package_list_clone = package_list_box.clone();
-    let current_packages_clone = current_packages.clone();
+    let ctx_clone = list_ctx.clone();
+    let start_page_clone = start_page.clone();
     let content_stack_clone = content_stack.clone();
-    let installed_clone = installed_packages.clone();
-    let bookmarks_clone = bookmarked_packages.clone();
+    let error_label_clone = error_label.clone();
+    let error_detail_clone = error_detail.clone();
+    let load_generation_clone = load_generation.clone();
+    let startup_generation = load_generation.get();
     glib::MainContext::default().spawn_local(async move {
-        match crate::aur_client::get_recent_packages(50).await {
-            Ok(mut packages) => {
-                packages.sort_by(|a, b| {
-                    b.popularity.unwrap_or(0.0).partial_cmp(&a.popularity.unwrap_or(0.0)).unwrap()
-                });
-                *current_packages_clone.borrow_mut() = packages.clone();
-                super::package_list::update_package_list(
-                    &package_list_clone,
-                    packages,
-                    &installed_clone,
-                    &bookmarks_clone,
-                );
-                content_stack_clone.set_visible_child_name("packages");
+        let installed = crate::package_checker::load_installed_packages().await;
+        ctx_clone.set_installed(installed);
+        // Rows may already exist if the start page was shown early
+        super::package_list::refresh_installed_state(&ctx_clone);
+        // The user may have searched in the meantime
+        if load_generation_clone.get() != startup_generation {
+            return;
+        }
+        load_start_page(
+            &ctx_clone,
+            &start_page_clone,
+            &content_stack_clone,
+            &error_label_clone,
+            &error_detail_clone,
+            &load_generation_clone,
+        );
+    });
+
+    // Retry and home button handlers
+    for button in [&retry_button, &home_btn] {
+        let ctx_clone = list_ctx.clone();
+        let start_page_clone = start_page.clone();
+        let content_stack_clone = content_stack.clone();
+        let error_label_clone = error_label.clone();
+        let error_detail_clone = error_detail.clone();
+        let load_generation_clone = load_generation.clone();
+        button.connect_clicked(move |_| {
+            load_start_page(
+                &ctx_clone,
+                &start_page_clone,
+                &content_stack_clone,
+                &error_label_clone,
+                &error_detail_clone,
+                &load_generation_clone,
+            );
+        });
+    }
+
+    // Live installed state: pacman rewrites local/ on every transaction
+    let installed_monitor = watch_installed(&list_ctx);
+    window.connect_destroy(move |_| {
+        if let Some(monitor) = &installed_monitor {
+            monitor.cancel();
+        }
+    });
+
+    // Response cache: drop entries too old to ever be served
+    let client_clone = client.clone();
+    glib::MainContext::default().spawn_local(async move {
+        if let Err(e) = client_clone.evict_cache().await {
+            eprintln!("Failed to evict old cache entries: {}", e);
+        }
+    });
+
+    // Offline index: load what we have, then revalidate if it is stale
+    let client_clone = client.clone();
+    let index_label_clone = index_label.clone();
+    let index_refresh_btn_clone = index_refresh_btn.clone();
+    glib::MainContext::default().spawn_local(async move {
+        index_label_clone.set_text("Loading offline index...");
+        if let Err(e) = client_clone.load_index().await {
+            eprintln!("No usable offline index: {}", e);
+        }
+        let stale = client_clone.index().is_none_or(|index| index.age() > INDEX_MAX_AGE);
+        if stale {
+            refresh_index(&client_clone, &index_label_clone, &index_refresh_btn_clone).await;
+        } else {
+            update_index_label(&client_clone, &index_label_clone);
+        }
+    });
+
+    let client_clone = client.clone();
+    let index_label_clone = index_label.clone();
+    index_refresh_btn.connect_clicked(move |btn| {
+        let client = client_clone.clone();
+        let index_label = index_label_clone.clone();
+        let btn = btn.clone();
+        glib::MainContext::default().spawn_local(async move {
+            refresh_index(&client, &index_label, &btn).await;
+        });
+    });
+
+    // Retry and rate-limit notices from the client
+    let mut status_rx = client.subscribe_status();
+    let toast_overlay_clone = toast_overlay.clone();
+    glib::MainContext::default().spawn_local(async move {
+        loop {
+            match status_rx.recv().await {
+                Ok(status) => {
+                    let toast = adw::Toast::new(&status.to_string());
+                    toast.set_timeout(3);
+                    toast_overlay_clone.add_toast(toast);
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
             }
-            Err(e) => {
-                eprintln!("Failed to load packages: {}", e);
-                content_stack_clone.set_visible_child_name("error");
+        }
+    });
+
+    // Clear cache action
+    let clear_cache_action = gio::SimpleAction::new("clear-cache", None);
+    let ctx_clone = list_ctx.clone();
+    let toast_overlay_clone = toast_overlay.clone();
+    clear_cache_action.connect_activate(move |_, _| {
+        let ctx = ctx_clone.clone();
+        let toast_overlay = toast_overlay_clone.clone();
+        glib::MainContext::default().spawn_local(async move {
+            ctx.details_cache.borrow_mut().clear();
+            let message = match ctx.client.clear_cache().await {
+                Ok(()) => "Cache cleared".to_string(),
+                Err(e) => format!("Failed to clear cache: {}", e),
+            };
+            toast_overlay.add_toast(adw::Toast::new(&message));
+        });
+    });
+    window.add_action(&clear_cache_action);
+
+    // AUR helper settings action
+    let helper_settings_action = gio::SimpleAction::new("helper-settings", None);
+    let window_clone = window.clone();
+    let ctx_clone = list_ctx.clone();
+    helper_settings_action.connect_activate(move |_, _| {
+        super::helper_settings::show_helper_settings(window_clone.upcast_ref(), &ctx_clone);
+    });
+    window.add_action(&helper_settings_action);
+
+    // Manifest import/export actions
+    let import_action = gio::SimpleAction::new("import-manifest", None);
+    let window_clone = window.clone();
+    let ctx_clone = list_ctx.clone();
+    let toast_overlay_clone = toast_overlay.clone();
+    import_action.connect_activate(move |_, _| {
+        let window = window_clone.clone();
+        let ctx = ctx_clone.clone();
+        let toast_overlay = toast_overlay_clone.clone();
+        glib::MainContext::default().spawn_local(async move {
+            super::manifest_dialog::import_manifest(window.upcast_ref(), &ctx, &toast_overlay).await;
+        });
+    });
+    window.add_action(&import_action);
+
+    let export_action = gio::SimpleAction::new("export-manifest", None);
+    let window_clone = window.clone();
+    let ctx_clone = list_ctx.clone();
+    let toast_overlay_clone = toast_overlay.clone();
+    export_action.connect_activate(move |_, _| {
+        let window = window_clone.clone();
+        let ctx = ctx_clone.clone();
+        let toast_overlay = toast_overlay_clone.clone();
+        glib::MainContext::default().spawn_local(async move {
+            super::manifest_dialog::export_manifest(window.upcast_ref(), &ctx, &toast_overlay).await;
+        });
+    });
+    window.add_action(&export_action);
+
+    // Search as you type
+    let search = Search {
+        ctx: list_ctx.clone(),
+        package_list: package_list_box.clone(),
+        current_packages: current_packages.clone(),
+        sort_dropdown: sort_dropdown.clone(),
+        field_dropdown: field_dropdown.clone(),
+        content_stack: content_stack.clone(),
+        error_label: error_label.clone(),
+        error_detail: error_detail.clone(),
+        generation: load_generation.clone(),
+        pending: Rc::new(RefCell::new(None)),
+        start_page: start_page.clone(),
+    };
+
+    let search_clone = search.clone();
+    let search_changed_handler = search_entry.connect_changed(move |entry| {
+        let query = entry.text().trim().to_string();
+        if query.is_empty() {
+            // Back to the start page; drop whatever search was in flight.
+            search_clone.show_start_page();
+        } else {
+            search_clone.schedule(query);
+        }
+    });
+
+    let search_clone = search.clone();
+    search_entry.connect_activate(move |entry| {
+        search_clone.run_now(entry.text().trim());
+    });
+
+    // Autocomplete: pick a package to open its details, or a package base
+    // to search for it.
+    let ctx_clone = list_ctx.clone();
+    let search_clone = search.clone();
+    let search_entry_clone = search_entry.clone();
+    let completion = Completion::attach(&search_entry, &client, move |completion, suggestion| {
+        match suggestion {
+            Suggestion::Package(name) => {
+                // Only the details are wanted, not a search for the name
+                search_entry_clone.block_signal(&search_changed_handler);
+                completion.set_entry_text(&name);
+                search_entry_clone.unblock_signal(&search_changed_handler);
+                search_clone.cancel_pending();
+                super::package_list::show_package_details_by_name(&ctx_clone, &name);
+            }
+            Suggestion::PackageBase(base) => {
+                completion.set_entry_text(&base);
+                search_clone.run_now(&base);
             }
         }
     });
 
-    // Retry button handler
+    let search_clone = search.clone();
+    let search_entry_clone = search_entry.clone();
+    field_dropdown.connect_selected_notify(move |dropdown| {
+        let field = SearchField::ALL
+            .get(dropdown.selected() as usize)
+            .copied()
+            .unwrap_or_default();
+        completion.set_enabled(matches!(field, SearchField::Name | SearchField::NameDesc));
+        search_clone.run_now(search_entry_clone.text().trim());
+    });
+
+    // Sort dropdown handler
     let package_list_clone = package_list_box.clone();
     let current_packages_clone = current_packages.clone();
+    let ctx_clone = list_ctx.clone();
+    sort_dropdown.connect_selected_notify(move |dropdown| {
+        let sort_mode = selected_sort_mode(dropdown);
+
+        let mut packages = current_packages_clone.borrow().clone();
+        if !packages.is_empty() {
+            sort_packages(&mut packages, sort_mode);
+            super::package_list::update_package_list(&package_list_clone, packages, &ctx_clone);
+        }
+    });
+
+    // Bookmarks page and button handler
+    let bookmarks_view = BookmarksView::new(&list_ctx);
+    content_stack.add_named(&bookmarks_view.widget, Some("bookmarks"));
+
     let content_stack_clone = content_stack.clone();
-    let installed_clone = installed_packages.clone();
-    let bookmarks_clone = bookmarked_packages.clone();
-    retry_button.connect_clicked(move |_| {
+    let error_label_clone = error_label.clone();
+    let error_detail_clone = error_detail.clone();
+    let search_clone = search.clone();
+    bookmarks_btn.connect_clicked(move |_| {
+        search_clone.cancel();
+        let generation = search_clone.generation.get();
         content_stack_clone.set_visible_child_name("loading");
-        let package_list = package_list_clone.clone();
-        let current_packages = current_packages_clone.clone();
+
+        let bookmarks_view = bookmarks_view.clone();
+        let generation_counter = search_clone.generation.clone();
         let content_stack = content_stack_clone.clone();
-        let installed = installed_clone.clone();
-        let bookmarks = bookmarks_clone.clone();
-        
+        let error_label = error_label_clone.clone();
+        let error_detail = error_detail_clone.clone();
         glib::MainContext::default().spawn_local(async move {
-            match crate::aur_client::get_recent_packages(50).await {
-                Ok(mut packages) => {
-                    packages.sort_by(|a, b| {
-                        b.popularity.unwrap_or(0.0).partial_cmp(&a.popularity.unwrap_or(0.0)).unwrap()
-                    });
-                    *current_packages.borrow_mut() = packages.clone();
-                    super::package_list::update_package_list(&package_list, packages, &installed, &bookmarks);
-                    content_stack.set_visible_child_name("packages");
-                }
+            let result = bookmarks_view.load().await;
+            if generation_counter.get() != generation {
+                return;
+            }
+
+            match result {
+                Ok(()) => content_stack.set_visible_child_name("bookmarks"),
                 Err(e) => {
-                    eprintln!("Failed to load packages: {}", e);
-                    content_stack.set_visible_child_name("error");
+                    eprintln!("Failed to load bookmarks: {}", e);
+                    show_error(&content_stack, &error_label, &error_detail, &e);
                 }
             }
         });
     });
 
-    // Search functionality
-    let package_list_clone = package_list_box.clone();
-    let current_packages_clone = current_packages.clone();
-    let sort_dropdown_clone = sort_dropdown.clone();
+    // Updates page and button handler
+    let updates_view = UpdatesView::new(&list_ctx);
+    content_stack.add_named(&updates_view.widget, Some("updates"));
+
     let content_stack_clone = content_stack.clone();
-    let installed_clone = installed_packages.clone();
-    let bookmarks_clone = bookmarked_packages.clone();
-    search_entry.connect_activate(move |entry| {
-        let query = entry.text().to_string();
-        if query.is_empty() {
+    let error_label_clone = error_label.clone();
+    let error_detail_clone = error_detail.clone();
+    let search_clone = search.clone();
+    updates_btn.connect_clicked(move |_| {
+        search_clone.cancel();
+        let generation = search_clone.generation.get();
+        content_stack_clone.set_visible_child_name("loading");
+
+        let updates_view = updates_view.clone();
+        let generation_counter = search_clone.generation.clone();
+        let content_stack = content_stack_clone.clone();
+        let error_label = error_label_clone.clone();
+        let error_detail = error_detail_clone.clone();
+        glib::MainContext::default().spawn_local(async move {
+            let result = updates_view.load().await;
+            if generation_counter.get() != generation {
+                return;
+            }
+
+            match result {
+                Ok(()) => content_stack.set_visible_child_name("updates"),
+                Err(e) => {
+                    eprintln!("Failed to check for updates: {}", e);
+                    show_error(&content_stack, &error_label, &error_detail, &e);
+                }
+            }
+        });
+    });
+
+    window
+}
+
+/// Watches pacman's local database and, once it has been quiet for
+/// `INSTALLED_DEBOUNCE`, rereads it and refreshes every package row.
+fn watch_installed(ctx: &ListContext) -> Option<gio::FileMonitor> {
+    let local_dir = gio::File::for_path(crate::package_checker::db_path().join("local"));
+    let monitor = match local_dir.monitor_directory(gio::FileMonitorFlags::NONE, None::<&gio::Cancellable>) {
+        Ok(monitor) => monitor,
+        Err(e) => {
+            eprintln!("Cannot watch the pacman database: {}", e);
+            return None;
+        }
+    };
+
+    let ctx = ctx.clone();
+    let pending: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
+    monitor.connect_changed(move |_, _, _, _| {
+        // A transaction touches many files; wait for it to settle
+        if let Some(source) = pending.borrow_mut().take() {
+            source.remove();
+        }
+        let ctx = ctx.clone();
+        let pending_clone = pending.clone();
+        let source = glib::timeout_add_local_once(INSTALLED_DEBOUNCE, move || {
+            pending_clone.borrow_mut().take();
+            glib::MainContext::default().spawn_local(async move {
+                let installed = crate::package_checker::load_installed_packages().await;
+                ctx.set_installed(installed);
+                super::package_list::refresh_installed_state(&ctx);
+            });
+        });
+        *pending.borrow_mut() = Some(source);
+    });
+
+    Some(monitor)
+}
+
+/// Debounced, cancellable search. Every load into the content area bumps
+/// `generation`, so a response that arrives after a newer query (or after
+/// the user navigated elsewhere) is dropped instead of overwriting it.
+#[derive(Clone)]
+struct Search {
+    ctx: ListContext,
+    package_list: gtk4::ListBox,
+    current_packages: Rc<RefCell<Vec<AurPackage>>>,
+    sort_dropdown: DropDown,
+    field_dropdown: DropDown,
+    content_stack: gtk4::Stack,
+    error_label: gtk4::Label,
+    error_detail: gtk4::Label,
+    generation: Rc<Cell<u64>>,
+    pending: Rc<RefCell<Option<glib::SourceId>>>,
+    start_page: StartPage,
+}
+
+impl Search {
+    fn schedule(&self, query: String) {
+        self.cancel_pending();
+        if query.chars().count() < MIN_QUERY_LEN {
+            // Results for the longer query still in flight must not show up
+            if self.content_stack.visible_child_name().as_deref() == Some("loading") {
+                self.show_start_page();
+            } else {
+                self.cancel();
+            }
             return;
         }
 
-        content_stack_clone.set_visible_child_name("loading");
-        let package_list = package_list_clone.clone();
-        let current_packages = current_packages_clone.clone();
-        let sort_dropdown = sort_dropdown_clone.clone();
-        let content_stack = content_stack_clone.clone();
-        let installed = installed_clone.clone();
-        let bookmarks = bookmarks_clone.clone();
-        
+        let this = self.clone();
+        let source = glib::timeout_add_local_once(SEARCH_DEBOUNCE, move || {
+            // The source is gone once it fires; forget it so nobody removes it.
+            this.pending.borrow_mut().take();
+            this.run(&query);
+        });
+        *self.pending.borrow_mut() = Some(source);
+    }
+
+    fn run_now(&self, query: &str) {
+        self.cancel_pending();
+        if query.chars().count() >= MIN_QUERY_LEN {
+            self.run(query);
+        }
+    }
+
+    /// Cancels any search and shows the start page, loading it again if
+    /// that was cancelled before it ever finished.
+    fn show_start_page(&self) {
+        self.cancel();
+        if self.start_page.loaded.get() {
+            self.content_stack.set_visible_child_name("start");
+        } else {
+            load_start_page(
+                &self.ctx,
+                &self.start_page,
+                &self.content_stack,
+                &self.error_label,
+                &self.error_detail,
+                &self.generation,
+            );
+        }
+    }
+
+    fn cancel(&self) {
+        self.cancel_pending();
+        self.generation.set(self.generation.get() + 1);
+    }
+
+    fn cancel_pending(&self) {
+        if let Some(source) = self.pending.borrow_mut().take() {
+            source.remove();
+        }
+    }
+
+    fn run(&self, query: &str) {
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
+        self.content_stack.set_visible_child_name("loading");
+
+        let field = SearchField::ALL
+            .get(self.field_dropdown.selected() as usize)
+            .copied()
+            .unwrap_or_default();
+        let query = query.to_string();
+        let this = self.clone();
+
         glib::MainContext::default().spawn_local(async move {
-            match crate::aur_client::search_aur(&query).await {
+            let result = this.ctx.client.search(&query, field).await;
+            if this.generation.get() != generation {
+                return;
+            }
+
+            match result {
                 Ok(mut packages) => {
                     if packages.is_empty() {
-                        content_stack.set_visible_child_name("empty");
+                        this.content_stack.set_visible_child_name("empty");
                         return;
                     }
-                    
-                    let sort_mode = match sort_dropdown.selected() {
-                        0 => SortMode::Popularity,
-                        1 => SortMode::Votes,
-                        2 => SortMode::Alphabetical,
-                        3 => SortMode::LastModified,
-                        _ => SortMode::Popularity,
-                    };
-                    sort_packages(&mut packages, sort_mode);
-                    *current_packages.borrow_mut() = packages.clone();
-                    super::package_list::update_package_list(&package_list, packages, &installed, &bookmarks);
-                    content_stack.set_visible_child_name("packages");
+
+                    sort_packages(&mut packages, selected_sort_mode(&this.sort_dropdown));
+                    *this.current_packages.borrow_mut() = packages.clone();
+                    super::package_list::update_package_list(&this.package_list, packages, &this.ctx);
+                    this.content_stack.set_visible_child_name("packages");
                 }
                 Err(e) => {
                     eprintln!("Search failed: {}", e);
-                    content_stack.set_visible_child_name("error");
+                    show_error(&this.content_stack, &this.error_label, &this.error_detail, &e);
                 }
             }
         });
-    });
+    }
+}
 
-    // Sort dropdown handler
-    let package_list_clone = package_list_box.clone();
-    let current_packages_clone = current_packages.clone();
-    let installed_clone = installed_packages.clone();
-    let bookmarks_clone = bookmarked_packages.clone();
-    sort_dropdown.connect_selected_notify(move |dropdown| {
-        let sort_mode = match dropdown.selected() {
-            0 => SortMode::Popularity,
-            1 => SortMode::Votes,
-            2 => SortMode::Alphabetical,
-            3 => SortMode::LastModified,
-            _ => SortMode::Popularity,
-        };
-        
-        let mut packages = current_packages_clone.borrow().clone();
-        if !packages.is_empty() {
-            sort_packages(&mut packages, sort_mode);
-            super::package_list::update_package_list(
-                &package_list_clone,
-                packages,
-                &installed_clone,
-                &bookmarks_clone,
-            );
+/// The start page's two feed lists.
+#[derive(Clone)]
+struct StartPage {
+    updated_list: gtk4::ListBox,
+    new_list: gtk4::ListBox,
+    /// Whether the lists have been filled at least once.
+    loaded: Rc<Cell<bool>>,
+}
+
+fn load_start_page(
+    ctx: &ListContext,
+    start_page: &StartPage,
+    content_stack: &gtk4::Stack,
+    error_label: &gtk4::Label,
+    error_detail: &gtk4::Label,
+    generation: &Rc<Cell<u64>>,
+) {
+    let this_generation = generation.get() + 1;
+    generation.set(this_generation);
+    content_stack.set_visible_child_name("loading");
+    let ctx = ctx.clone();
+    let generation = generation.clone();
+    let start_page = start_page.clone();
+    let content_stack = content_stack.clone();
+    let error_label = error_label.clone();
+    let error_detail = error_detail.clone();
+
+    glib::MainContext::default().spawn_local(async move {
+        let result = fetch_start_page(&ctx.client).await;
+        if generation.get() != this_generation {
+            return;
         }
-    });
 
-    // Bookmarks button handler
-    let package_list_clone = package_list_box.clone();
-    let bookmarked_clone = bookmarked_packages.clone();
-    let current_packages_clone = current_packages.clone();
-    let content_stack_clone = content_stack.clone();
-    let installed_clone = installed_packages.clone();
-    bookmarks_btn.connect_clicked(move |_| {
-        let bookmarked = bookmarked_clone.borrow();
-        let all_packages = current_packages_clone.borrow();
-        
-        let bookmarked_list: Vec<_> = all_packages
-            .iter()
-            .filter(|p| bookmarked.contains(&p.name))
-            .cloned()
-            .collect();
-        
-        if bookmarked_list.is_empty() {
-            content_stack_clone.set_visible_child_name("empty");
-        } else {
-            super::package_list::update_package_list(
-                &package_list_clone,
-                bookmarked_list,
-                &installed_clone,
-                &bookmarked_clone,
-            );
-            content_stack_clone.set_visible_child_name("packages");
+        match result {
+            Ok((updated, new)) => {
+                super::package_list::update_package_list(&start_page.updated_list, updated, &ctx);
+                super::package_list::update_package_list(&start_page.new_list, new, &ctx);
+                start_page.loaded.set(true);
+                content_stack.set_visible_child_name("start");
+            }
+            Err(e) => {
+                eprintln!("Failed to load packages: {}", e);
+                show_error(&content_stack, &error_label, &error_detail, &e);
+            }
         }
     });
+}
 
-    window
+/// Reads both RSS feeds and resolves their entries with a single batched
+/// info lookup, keeping feed order. Entries for packages deleted since the
+/// feed was generated are dropped.
+async fn fetch_start_page(client: &AurClient) -> Result<(Vec<AurPackage>, Vec<AurPackage>), AurError> {
+    let (updated, new) = tokio::join!(client.updated_packages(), client.new_packages());
+    let (updated, new) = (updated?, new?);
+
+    let names: Vec<&str> = updated.iter().chain(new.iter()).map(|e| e.name.as_str()).collect();
+    let packages: HashMap<String, AurPackage> = client
+        .info_many(&names)
+        .await?
+        .into_iter()
+        .map(|p| (p.name.clone(), p))
+        .collect();
+
+    let resolve = |entries: &[FeedEntry]| -> Vec<AurPackage> {
+        entries.iter().filter_map(|e| packages.get(&e.name).cloned()).collect()
+    };
+    Ok((resolve(&updated), resolve(&new)))
+}
+
+async fn refresh_index(client: &AurClient, index_label: &gtk4::Label, refresh_btn: &Button) {
+    refresh_btn.set_sensitive(false);
+    index_label.set_text("Updating offline index...");
+    index_label.set_tooltip_text(None);
+    match client.refresh_index().await {
+        Ok(()) => update_index_label(client, index_label),
+        Err(e) => {
+            eprintln!("Failed to refresh offline index: {}", e);
+            update_index_label(client, index_label);
+            index_label.set_tooltip_text(Some(&format!("Last refresh failed: {}", e)));
+        }
+    }
+    refresh_btn.set_sensitive(true);
+}
+
+fn update_index_label(client: &AurClient, index_label: &gtk4::Label) {
+    match client.index() {
+        Some(index) => {
+            index_label.set_text(&format!("Offline index: {}", format_age(index.age())));
+            index_label.set_tooltip_text(Some(&format!(
+                "{} packages available offline",
+                index.package_count()
+            )));
+        }
+        None => {
+            index_label.set_text("No offline index");
+            index_label.set_tooltip_text(None);
+        }
+    }
+}
+
+fn format_age(age: Duration) -> String {
+    let minutes = age.as_secs() / 60;
+    if minutes < 1 {
+        "just updated".to_string()
+    } else if minutes < 60 {
+        format!("{} min old", minutes)
+    } else if minutes < 60 * 24 {
+        format!("{} h old", minutes / 60)
+    } else {
+        format!("{} d old", minutes / (60 * 24))
+    }
+}
+
+fn show_error(
+    content_stack: &gtk4::Stack,
+    error_label: &gtk4::Label,
+    error_detail: &gtk4::Label,
+    error: &AurError,
+) {
+    let title = match error {
+        AurError::Network(_) => "Failed to connect to AUR",
+        AurError::Timeout => "AUR did not respond",
+        AurError::Http(_) => "AUR returned an error",
+        AurError::Json(_) => "Unexpected response from AUR",
+        AurError::Rpc(_) => "AUR rejected the request",
+        AurError::NotFound(_) => "Package not found",
+        AurError::Io(_) => "Could not read feed",
+        AurError::Feed(_) => "Unexpected response from AUR",
+        AurError::RateLimited(_) => "AUR rate limit reached",
+    };
+    error_label.set_text(title);
+    error_detail.set_text(&error.to_string());
+    content_stack.set_visible_child_name("error");
+}
+
+fn selected_sort_mode(dropdown: &DropDown) -> SortMode {
+    match dropdown.selected() {
+        0 => SortMode::Popularity,
+        1 => SortMode::Votes,
+        2 => SortMode::Alphabetical,
+        3 => SortMode::LastModified,
+        _ => SortMode::Popularity,
+    }
 }
 
-fn sort_packages(packages: &mut Vec<crate::aur_client::AurPackage>, mode: SortMode) {
+fn sort_packages(packages: &mut Vec<AurPackage>, mode: SortMode) {
     match mode {
         SortMode::Popularity => {
             packages.sort_by(|a, b| {
@@ -311,9 +859,7 @@ fn sort_packages(packages: &mut Vec<crate::aur_client::AurPackage>, mode: SortMo
             packages.sort_by(|a, b| a.name.cmp(&b.name));
         }
         SortMode::LastModified => {
-            packages.sort_by(|a, b| {
-                b.last_modified.unwrap_or(0).cmp(&a.last_modified.unwrap_or(0))
-            });
+            packages.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
         }
     }
 }
@@ -0,0 +1,31 @@
+use crate::aur_client::{AurClient, AurError, AurPackage};
+use crate::version::Version;
+use std::collections::HashMap;
+
+/// An installed foreign package with a newer version in the AUR.
+#[derive(Debug, Clone)]
+pub struct Update {
+    pub installed_version: Version,
+    pub package: AurPackage,
+}
+
+/// Looks up every installed foreign package (name → installed version) in
+/// the AUR and returns the ones whose AUR version is newer, by name.
+/// Packages the AUR does not know (local builds, removed packages) are
+/// skipped.
+pub async fn find_updates(client: &AurClient, installed: HashMap<String, Version>) -> Result<Vec<Update>, AurError> {
+    let names: Vec<&str> = installed.keys().map(String::as_str).collect();
+    let packages = client.info_many(&names).await?;
+
+    let mut updates: Vec<Update> = packages
+        .into_iter()
+        .filter_map(|package| {
+            let installed_version = installed.get(&package.name)?.clone();
+            let newer = Version::parse(&package.version) > installed_version;
+            newer.then_some(Update { installed_version, package })
+        })
+        .collect();
+
+    updates.sort_by(|a, b| a.package.name.cmp(&b.package.name));
+    Ok(updates)
+}
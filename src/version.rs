@@ -0,0 +1,347 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A pacman package version, `[epoch:]pkgver[-pkgrel]`. Ordering follows
+/// `alpm_pkg_vercmp`: epoch first, then pkgver, then pkgrel only when both
+/// sides have one, each part compared with rpmvercmp.
+#[derive(Debug, Clone)]
+pub struct Version {
+    raw: String,
+    epoch: String,
+    pkgver: String,
+    pkgrel: Option<String>,
+}
+
+impl Version {
+    /// Splits a version string the way libalpm's `parseEVR` does. Every
+    /// string is accepted; odd ones just compare oddly, as in pacman.
+    pub fn parse(version: &str) -> Self {
+        let digits = version.len() - version.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let (epoch, rest) = match version[digits..].strip_prefix(':') {
+            Some(rest) if digits > 0 => (&version[..digits], rest),
+            Some(rest) => ("0", rest),
+            None => ("0", version),
+        };
+        let (pkgver, pkgrel) = match rest.rsplit_once('-') {
+            Some((pkgver, pkgrel)) => (pkgver, Some(pkgrel.to_string())),
+            None => (rest, None),
+        };
+
+        Version {
+            raw: version.to_string(),
+            epoch: epoch.to_string(),
+            pkgver: pkgver.to_string(),
+            pkgrel,
+        }
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.raw == other.raw {
+            return Ordering::Equal;
+        }
+        rpmvercmp(&self.epoch, &other.epoch)
+            .then_with(|| rpmvercmp(&self.pkgver, &other.pkgver))
+            .then_with(|| match (&self.pkgrel, &other.pkgrel) {
+                (Some(a), Some(b)) => rpmvercmp(a, b),
+                _ => Ordering::Equal,
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+/// rpm's segment-wise comparison as used by libalpm: runs of digits compare
+/// numerically, runs of letters lexically, a number beats letters, and a
+/// trailing letter segment (`1.0alpha`) sorts before the bare version.
+fn rpmvercmp(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+
+    let one = a.as_bytes();
+    let two = b.as_bytes();
+    // `i`/`j` walk the strings; `seg1`/`seg2` mark where the previous
+    // segment ended, so the separator runs in between can be measured.
+    let (mut i, mut j) = (0, 0);
+    let (mut seg1, mut seg2) = (0, 0);
+
+    while i < one.len() && j < two.len() {
+        while i < one.len() && !one[i].is_ascii_alphanumeric() {
+            i += 1;
+        }
+        while j < two.len() && !two[j].is_ascii_alphanumeric() {
+            j += 1;
+        }
+        if i >= one.len() || j >= two.len() {
+            break;
+        }
+
+        // Differing separator lengths decide on their own.
+        if i - seg1 != j - seg2 {
+            return (i - seg1).cmp(&(j - seg2));
+        }
+
+        let is_num = one[i].is_ascii_digit();
+        let class = |c: &u8| if is_num { c.is_ascii_digit() } else { c.is_ascii_alphabetic() };
+        seg1 = i + one[i..].iter().take_while(|c| class(c)).count();
+        seg2 = j + two[j..].iter().take_while(|c| class(c)).count();
+
+        // Segments of different kinds: numbers are newer than letters.
+        if seg2 == j {
+            return if is_num { Ordering::Greater } else { Ordering::Less };
+        }
+
+        let mut part1 = &one[i..seg1];
+        let mut part2 = &two[j..seg2];
+        if is_num {
+            while part1.first() == Some(&b'0') {
+                part1 = &part1[1..];
+            }
+            while part2.first() == Some(&b'0') {
+                part2 = &part2[1..];
+            }
+            let by_len = part1.len().cmp(&part2.len());
+            if by_len != Ordering::Equal {
+                return by_len;
+            }
+        }
+        let by_bytes = part1.cmp(part2);
+        if by_bytes != Ordering::Equal {
+            return by_bytes;
+        }
+
+        i = seg1;
+        j = seg2;
+    }
+
+    let rest1 = &one[i..];
+    let rest2 = &two[j..];
+    if rest1.is_empty() && rest2.is_empty() {
+        return Ordering::Equal;
+    }
+    // Whichever side has something left is newer, except that a leftover
+    // letter segment never beats nothing.
+    let starts_alpha = |rest: &[u8]| rest.first().is_some_and(u8::is_ascii_alphabetic);
+    if (rest1.is_empty() && !starts_alpha(rest2)) || starts_alpha(rest1) {
+        Ordering::Less
+    } else {
+        Ordering::Greater
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Constraint {
+    Less,
+    LessEqual,
+    Equal,
+    GreaterEqual,
+    Greater,
+}
+
+impl Constraint {
+    fn symbol(self) -> &'static str {
+        match self {
+            Constraint::Less => "<",
+            Constraint::LessEqual => "<=",
+            Constraint::Equal => "=",
+            Constraint::GreaterEqual => ">=",
+            Constraint::Greater => ">",
+        }
+    }
+}
+
+/// A dependency, provides or conflicts entry: a name with an optional
+/// version constraint, e.g. `foo`, `foo>=1.2` or `bar=3:1.0-2`.
+#[derive(Debug, Clone)]
+pub struct Dependency {
+    pub name: String,
+    pub constraint: Option<(Constraint, Version)>,
+}
+
+impl Dependency {
+    /// Parses an entry; an optdepends description (`foo: for bar`) is
+    /// dropped.
+    pub fn parse(entry: &str) -> Self {
+        let entry = entry.split(": ").next().unwrap_or(entry).trim();
+        let Some(start) = entry.find(['<', '>', '=']) else {
+            return Dependency { name: entry.to_string(), constraint: None };
+        };
+
+        let (name, rest) = entry.split_at(start);
+        let (constraint, version) = [
+            (">=", Constraint::GreaterEqual),
+            ("<=", Constraint::LessEqual),
+            ("=", Constraint::Equal),
+            (">", Constraint::Greater),
+            ("<", Constraint::Less),
+        ]
+        .into_iter()
+        .find_map(|(symbol, constraint)| rest.strip_prefix(symbol).map(|version| (constraint, version)))
+        .unwrap_or((Constraint::Equal, rest));
+
+        Dependency {
+            name: name.trim().to_string(),
+            constraint: Some((constraint, Version::parse(version.trim()))),
+        }
+    }
+
+    /// Whether `version` meets the constraint, ignoring the name.
+    pub fn allows(&self, version: &Version) -> bool {
+        let Some((constraint, required)) = &self.constraint else {
+            return true;
+        };
+        let ordering = version.cmp(required);
+        match constraint {
+            Constraint::Less => ordering == Ordering::Less,
+            Constraint::LessEqual => ordering != Ordering::Greater,
+            Constraint::Equal => ordering == Ordering::Equal,
+            Constraint::GreaterEqual => ordering != Ordering::Less,
+            Constraint::Greater => ordering == Ordering::Greater,
+        }
+    }
+}
+
+impl fmt::Display for Dependency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.constraint {
+            Some((constraint, version)) => write!(f, "{}{}{}", self.name, constraint.symbol(), version),
+            None => f.write_str(&self.name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// pacman's `test/util/vercmptest.sh` table.
+    const VERCMP_CASES: &[(&str, &str, Ordering)] = &[
+        // all similar length, no pkgrel
+        ("1.5.0", "1.5.0", Ordering::Equal),
+        ("1.5.1", "1.5.0", Ordering::Greater),
+        // mixed length
+        ("1.5.1", "1.5", Ordering::Greater),
+        // with pkgrel, simple
+        ("1.5.0-1", "1.5.0-1", Ordering::Equal),
+        ("1.5.0-1", "1.5.0-2", Ordering::Less),
+        ("1.5.0-1", "1.5.1-1", Ordering::Less),
+        ("1.5.0-2", "1.5.1-1", Ordering::Less),
+        // with pkgrel, mixed lengths
+        ("1.5-1", "1.5.1-1", Ordering::Less),
+        ("1.5-2", "1.5.1-1", Ordering::Less),
+        ("1.5-2", "1.5.1-2", Ordering::Less),
+        // mixed pkgrel inclusion
+        ("1.5", "1.5-1", Ordering::Equal),
+        ("1.5-1", "1.5", Ordering::Equal),
+        ("1.1-1", "1.1", Ordering::Equal),
+        ("1.0-1", "1.1", Ordering::Less),
+        ("1.1-1", "1.0", Ordering::Greater),
+        // alphanumeric versions
+        ("1.5b-1", "1.5-1", Ordering::Less),
+        ("1.5b", "1.5", Ordering::Less),
+        ("1.5b-1", "1.5", Ordering::Less),
+        ("1.5b", "1.5.1", Ordering::Less),
+        // from the manpage
+        ("1.0a", "1.0alpha", Ordering::Less),
+        ("1.0alpha", "1.0b", Ordering::Less),
+        ("1.0b", "1.0beta", Ordering::Less),
+        ("1.0beta", "1.0rc", Ordering::Less),
+        ("1.0rc", "1.0", Ordering::Less),
+        // alpha-dotted versions
+        ("1.5.a", "1.5", Ordering::Greater),
+        ("1.5.b", "1.5.a", Ordering::Greater),
+        ("1.5.1", "1.5.b", Ordering::Greater),
+        // alpha dots and dashes
+        ("1.5.b-1", "1.5.b", Ordering::Equal),
+        ("1.5-1", "1.5.b", Ordering::Less),
+        // same/similar content, differing separators
+        ("2.0", "2_0", Ordering::Equal),
+        ("2.0_a", "2_0.a", Ordering::Equal),
+        ("2.0a", "2.0.a", Ordering::Less),
+        ("2___a", "2_a", Ordering::Greater),
+        // epoch included version comparisons
+        ("0:1.0", "0:1.0", Ordering::Equal),
+        ("0:1.0", "0:1.1", Ordering::Less),
+        ("1:1.0", "0:1.0", Ordering::Greater),
+        ("1:1.0", "0:1.1", Ordering::Greater),
+        ("1:1.0", "2:1.1", Ordering::Less),
+        // epoch + sometimes present pkgrel
+        ("1:1.0", "0:1.0-1", Ordering::Greater),
+        ("1:1.0-1", "0:1.1-1", Ordering::Greater),
+        // epoch included on one version
+        ("0:1.0", "1.0", Ordering::Equal),
+        ("0:1.0", "1.1", Ordering::Less),
+        ("0:1.1", "1.0", Ordering::Greater),
+        ("1:1.0", "1.0", Ordering::Greater),
+        ("1:1.0", "1.1", Ordering::Greater),
+        ("1:1.1", "1.1", Ordering::Greater),
+    ];
+
+    #[test]
+    fn matches_pacman_vercmp() {
+        for &(a, b, expected) in VERCMP_CASES {
+            assert_eq!(Version::parse(a).cmp(&Version::parse(b)), expected, "{} vs {}", a, b);
+            assert_eq!(Version::parse(b).cmp(&Version::parse(a)), expected.reverse(), "{} vs {}", b, a);
+        }
+    }
+
+    #[test]
+    fn parses_constraints() {
+        let dependency = Dependency::parse("foo>=1.2");
+        assert_eq!(dependency.name, "foo");
+        assert!(matches!(dependency.constraint, Some((Constraint::GreaterEqual, _))));
+        assert_eq!(dependency.to_string(), "foo>=1.2");
+
+        let dependency = Dependency::parse("bar=3:1.0-2");
+        assert_eq!(dependency.name, "bar");
+        assert!(matches!(dependency.constraint, Some((Constraint::Equal, _))));
+        assert_eq!(dependency.to_string(), "bar=3:1.0-2");
+
+        let dependency = Dependency::parse("baz: for qux support");
+        assert_eq!(dependency.name, "baz");
+        assert!(dependency.constraint.is_none());
+    }
+
+    #[test]
+    fn evaluates_constraints() {
+        let allows = |entry: &str, version: &str| Dependency::parse(entry).allows(&Version::parse(version));
+
+        assert!(allows("foo>=1.2", "1.2"));
+        assert!(allows("foo>=1.2", "1.2-1"));
+        assert!(allows("foo>=1.2", "1.10"));
+        assert!(!allows("foo>=1.2", "1.1"));
+        assert!(!allows("foo>=1.2", "1.2rc1"));
+
+        assert!(allows("bar=3:1.0-2", "3:1.0-2"));
+        // pkgrel only counts when both sides have one
+        assert!(allows("bar=3:1.0-2", "3:1.0"));
+        assert!(!allows("bar=3:1.0-2", "3:1.0-1"));
+        assert!(!allows("bar=3:1.0-2", "1.0-2"));
+
+        assert!(allows("qux<2", "1.9"));
+        assert!(!allows("qux<2", "2"));
+        assert!(allows("qux<=2", "2"));
+        assert!(allows("qux>1:0", "1:0.1"));
+        assert!(allows("qux", "0.0.1"));
+    }
+}